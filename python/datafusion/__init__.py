@@ -33,20 +33,34 @@ from .context import (
     SQLOptions,
 )
 
-from .catalog import Catalog, Database, Table
+from .catalog import (
+    Catalog,
+    CatalogProvider,
+    Database,
+    SchemaProvider,
+    Table,
+    TableProvider,
+)
 
 # The following imports are okay to remain as opaque to the user.
-from ._internal import Config
+from ._internal import CancellationToken, Config, QueryCancelledError
 
 from .record_batch import RecordBatchStream, RecordBatch
 
-from .udf import ScalarUDF, AggregateUDF, Accumulator, WindowUDF
+from .udf import (
+    ScalarUDF,
+    AggregateUDF,
+    Accumulator,
+    GroupsAccumulator,
+    TableFunction,
+    WindowUDF,
+)
 
 from .common import (
     DFSchema,
 )
 
-from .dataframe import DataFrame
+from .dataframe import DataFrame, ParquetColumnOptions, ParquetWriterOptions
 
 from .expr import (
     Expr,
@@ -61,8 +75,12 @@ __version__ = importlib_metadata.version(__name__)
 
 __all__ = [
     "Accumulator",
+    "GroupsAccumulator",
+    "CancellationToken",
     "Config",
     "DataFrame",
+    "ParquetColumnOptions",
+    "ParquetWriterOptions",
     "SessionContext",
     "SessionConfig",
     "SQLOptions",
@@ -76,12 +94,17 @@ __all__ = [
     "lit",
     "DFSchema",
     "Catalog",
+    "CatalogProvider",
     "Database",
+    "SchemaProvider",
     "Table",
+    "TableProvider",
     "AggregateUDF",
+    "TableFunction",
     "WindowUDF",
     "LogicalPlan",
     "ExecutionPlan",
+    "QueryCancelledError",
     "RecordBatch",
     "RecordBatchStream",
     "common",
@@ -117,3 +140,5 @@ udf = ScalarUDF.udf
 udaf = AggregateUDF.udaf
 
 udwf = WindowUDF.udwf
+
+udtf = TableFunction.udtf
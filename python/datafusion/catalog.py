@@ -21,10 +21,13 @@ from __future__ import annotations
 
 import datafusion._internal as df_internal
 
-from typing import TYPE_CHECKING
+from abc import ABCMeta, abstractmethod
+from enum import Enum
+from typing import TYPE_CHECKING, Any, Iterable, Optional
 
 if TYPE_CHECKING:
     import pyarrow
+    from datafusion.expr import Expr
 
 
 class Catalog:
@@ -34,6 +37,16 @@ class Catalog:
         """This constructor is not typically called by the end user."""
         self.catalog = catalog
 
+    @staticmethod
+    def memory_catalog() -> Catalog:
+        """Create a new, empty in-memory catalog.
+
+        Register it with
+        :py:func:`~datafusion.context.SessionContext.register_catalog` to make
+        it available to queries.
+        """
+        return Catalog(df_internal.Catalog.memory_catalog())
+
     def names(self) -> list[str]:
         """Returns the list of databases in this catalog."""
         return self.catalog.names()
@@ -42,6 +55,25 @@ class Catalog:
         """Returns the database with the given ``name`` from this catalog."""
         return Database(self.catalog.database(name))
 
+    def register_schema(self, name: str, schema: Database | SchemaProvider) -> None:
+        """Add a database with the given ``name`` to this catalog.
+
+        Args:
+            name: Name of the database.
+            schema: DataFusion database or Python :py:class:`SchemaProvider`.
+        """
+        self.catalog.register_schema(name, schema)
+
+    def deregister_schema(self, name: str, cascade: bool = True) -> None:
+        """Remove the database with the given ``name`` from this catalog.
+
+        Args:
+            name: Name of the database.
+            cascade: If ``False``, the database is only removed if it contains
+                no tables.
+        """
+        self.catalog.deregister_schema(name, cascade)
+
 
 class Database:
     """DataFusion Database."""
@@ -50,6 +82,14 @@ class Database:
         """This constructor is not typically called by the end user."""
         self.db = db
 
+    @staticmethod
+    def memory_schema() -> Database:
+        """Create a new, empty in-memory database.
+
+        Add it to a catalog with :py:func:`Catalog.register_schema`.
+        """
+        return Database(df_internal.Database.memory_schema())
+
     def names(self) -> set[str]:
         """Returns the list of all tables in this database."""
         return self.db.names()
@@ -58,6 +98,20 @@ class Database:
         """Return the table with the given ``name`` from this database."""
         return Table(self.db.table(name))
 
+    def register_table(self, name: str, table: Table | TableProvider) -> None:
+        """Add a table with the given ``name`` to this database.
+
+        Args:
+            name: Name of the table.
+            table: DataFusion table, Python :py:class:`TableProvider` or an object
+                accepted by :py:meth:`Table.from_pycapsule`.
+        """
+        self.db.register_table(name, table)
+
+    def deregister_table(self, name: str) -> None:
+        """Remove the table with the given ``name`` from this database."""
+        self.db.deregister_table(name)
+
 
 class Table:
     """DataFusion table."""
@@ -66,6 +120,38 @@ class Table:
         """This constructor is not typically called by the end user."""
         self.table = table
 
+    @staticmethod
+    def from_provider(provider: TableProvider) -> Table:
+        """Create a :py:class:`Table` from a Python :py:class:`TableProvider`.
+
+        The resulting table can be registered with
+        :py:func:`~datafusion.context.SessionContext.register_table` and
+        referenced from SQL statements.
+        """
+        return Table(df_internal.Table.from_provider(provider))
+
+    @staticmethod
+    def from_pycapsule(provider: Any) -> Table:
+        """Create a :py:class:`Table` from a provider implemented in Rust.
+
+        ``provider`` must either be a PyCapsule named ``datafusion_table_provider``
+        or an object providing one from a ``__datafusion_table_provider__``
+        method. The capsule holds the ``FFI_TableProvider`` C interface of the
+        ``datafusion-ffi`` crate, so the provider can be compiled into a separate
+        library. Such tables are scanned without acquiring the GIL.
+
+        Example usage::
+
+            from my_rust_plugin import MyTableProvider
+
+            ctx.register_table("my_table", Table.from_pycapsule(MyTableProvider()))
+        """
+        return Table(df_internal.Table.from_pycapsule(provider))
+
+    def __datafusion_table_provider__(self) -> Any:
+        """Export this table in a PyCapsule to be used by other libraries."""
+        return self.table.__datafusion_table_provider__()
+
     def schema(self) -> pyarrow.Schema:
         """Returns the schema associated with this table."""
         return self.table.schema()
@@ -74,3 +160,158 @@ class Table:
     def kind(self) -> str:
         """Returns the kind of table."""
         return self.table.kind()
+
+
+class TableProviderFilterPushDown(Enum):
+    """Indicates how a filter expression is handled by a :py:class:`TableProvider`.
+
+    The ``str`` equivalent is the lower case value of the name (``"unsupported"``,
+    ``"inexact"`` or ``"exact"``) and may be returned in place of this enumeration.
+    """
+
+    Unsupported = 1
+    """The filter cannot be used by the provider and will not be pushed down."""
+
+    Inexact = 2
+    """The filter can be used to prune data, but DataFusion will still apply it
+    to the returned rows."""
+
+    Exact = 3
+    """The provider guarantees that all returned rows satisfy the filter."""
+
+    def __str__(self):
+        """Returns the string equivalent."""
+        return self.name.lower()
+
+
+class TableProvider(metaclass=ABCMeta):
+    """Defines a table whose data is produced by Python code.
+
+    Implementations can be registered with
+    :py:func:`~datafusion.context.SessionContext.register_table` and queried with
+    SQL or the :py:class:`~datafusion.dataframe.DataFrame` API. Projections,
+    filters and limits from the query are passed to :py:func:`scan` so that the
+    provider can avoid producing data that is not needed.
+
+    .. code-block:: python
+
+        import pyarrow as pa
+
+        class Numbers(TableProvider):
+            def schema(self) -> pa.Schema:
+                return pa.schema([("a", pa.int64())])
+
+            def scan(self, projection, filters, limit):
+                batch = pa.RecordBatch.from_pydict({"a": [1, 2, 3]})
+                if projection is not None:
+                    batch = batch.select(projection)
+                return [batch]
+
+        ctx.register_table("numbers", Numbers())
+    """
+
+    @abstractmethod
+    def schema(self) -> pyarrow.Schema:
+        """Return the schema of the full table."""
+        pass
+
+    @abstractmethod
+    def scan(
+        self,
+        projection: Optional[list[int]],
+        filters: list[Expr],
+        limit: Optional[int],
+    ) -> Iterable[pyarrow.RecordBatch] | Any:
+        """Produce the data for a query against this table.
+
+        This is called each time a plan referencing the table is executed.
+
+        Args:
+            projection: Indices of the columns of :py:func:`schema` to return, or
+                ``None`` if all columns are required. Returned batches must match
+                the projected schema.
+            filters: Filters for which :py:func:`supports_filters_pushdown`
+                returned ``inexact`` or ``exact``. All filters are combined
+                with ``AND``.
+            limit: If set, the query requires at most this many rows. This is
+                only a hint and more rows may be returned.
+
+        Returns:
+            An iterable of :py:class:`pyarrow.RecordBatch` or any object
+            implementing ``__arrow_c_stream__``, such as a
+            :py:class:`pyarrow.RecordBatchReader`.
+        """
+        pass
+
+    def supports_filters_pushdown(
+        self, filters: list[Expr]
+    ) -> list[TableProviderFilterPushDown | str]:
+        """Report which of the ``filters`` can be handled by :py:func:`scan`.
+
+        By default no filters are pushed down.
+
+        Returns:
+            One :py:class:`TableProviderFilterPushDown` for each filter.
+        """
+        return [TableProviderFilterPushDown.Unsupported] * len(filters)
+
+    def statistics(self) -> Optional[dict[str, int]]:
+        """Return statistics about the table, if known.
+
+        The supported keys are ``num_rows`` and ``total_byte_size``. Both
+        values are treated as exact by the query planner. This method is called
+        once per scan. Exceptions it raises are reported through
+        :py:func:`sys.unraisablehook` and the statistics are treated as unknown.
+        """
+        return None
+
+
+class SchemaProvider(metaclass=ABCMeta):
+    """Defines a database whose tables are resolved by Python code.
+
+    Tables are looked up each time a query is planned, which allows the
+    provider to resolve them lazily, for example against an external metastore.
+    Register an implementation with
+    :py:func:`~datafusion.context.SessionContext.register_schema` or return it
+    from :py:func:`CatalogProvider.schema`.
+
+    Exceptions raised by :py:meth:`table` fail the query. Exceptions raised by
+    the other methods are reported through :py:func:`sys.unraisablehook` and
+    the method is treated as returning no tables.
+    """
+
+    @abstractmethod
+    def table_names(self) -> list[str]:
+        """Return the names of all tables in this database."""
+        pass
+
+    @abstractmethod
+    def table(self, name: str) -> Table | TableProvider | None:
+        """Return the table with the given ``name`` or ``None`` if it does not exist."""
+        pass
+
+    def table_exist(self, name: str) -> bool:
+        """Return whether a table with the given ``name`` exists."""
+        return name in self.table_names()
+
+
+class CatalogProvider(metaclass=ABCMeta):
+    """Defines a catalog whose databases are resolved by Python code.
+
+    Register an implementation with
+    :py:func:`~datafusion.context.SessionContext.register_catalog`.
+
+    Exceptions raised by its methods are reported through
+    :py:func:`sys.unraisablehook` and the method is treated as returning no
+    databases.
+    """
+
+    @abstractmethod
+    def schema_names(self) -> list[str]:
+        """Return the names of all databases in this catalog."""
+        pass
+
+    @abstractmethod
+    def schema(self, name: str) -> Database | SchemaProvider | None:
+        """Return the database with the given ``name`` or ``None`` if not found."""
+        pass
@@ -24,11 +24,18 @@ from ._internal import RuntimeConfig as RuntimeConfigInternal
 from ._internal import SQLOptions as SQLOptionsInternal
 from ._internal import SessionContext as SessionContextInternal
 
-from datafusion.catalog import Catalog, Table
+from datafusion.catalog import (
+    Catalog,
+    CatalogProvider,
+    Database,
+    SchemaProvider,
+    Table,
+    TableProvider,
+)
 from datafusion.dataframe import DataFrame
 from datafusion.expr import Expr, SortExpr, sort_list_to_raw_sort_list
 from datafusion.record_batch import RecordBatchStream
-from datafusion.udf import ScalarUDF, AggregateUDF, WindowUDF
+from datafusion.udf import ScalarUDF, AggregateUDF, TableFunction, WindowUDF
 
 from typing import Any, TYPE_CHECKING, Protocol
 from typing_extensions import deprecated
@@ -38,6 +45,7 @@ if TYPE_CHECKING:
     import pandas
     import polars
     import pathlib
+    from typing import Callable, Iterable
     from datafusion.plan import LogicalPlan, ExecutionPlan
 
 
@@ -371,6 +379,25 @@ class RuntimeConfig:
         self.config_internal = self.config_internal.with_temp_file_path(str(path))
         return self
 
+    def with_worker_threads(self, threads: int) -> RuntimeConfig:
+        """Use the specified number of threads to execute queries.
+
+        The threads are shared by every :py:class:`SessionContext` in the process, so
+        this setting must be applied before the first query is executed.
+
+        Args:
+            threads: Number of worker threads, must be greater than zero.
+
+        Returns:
+            A new :py:class:`RuntimeConfig` object with the updated setting.
+
+        Example usage::
+
+            config = RuntimeConfig().with_worker_threads(4)
+        """
+        self.config_internal = self.config_internal.with_worker_threads(threads)
+        return self
+
 
 class SQLOptions:
     """Options to be used when performing SQL queries."""
@@ -487,11 +514,13 @@ class SessionContext:
     def register_listing_table(
         self,
         name: str,
-        path: str | pathlib.Path,
+        path: str | pathlib.Path | list[str | pathlib.Path],
         table_partition_cols: list[tuple[str, str]] | None = None,
-        file_extension: str = ".parquet",
+        file_extension: str | None = None,
         schema: pyarrow.Schema | None = None,
         file_sort_order: list[list[Expr | SortExpr]] | None = None,
+        file_format: str = "parquet",
+        format_options: dict[str, Any] | None = None,
     ) -> None:
         """Register multiple files as a single table.
 
@@ -499,13 +528,38 @@ class SessionContext:
         files from locations in an :py:class:`~datafusion.object_store.ObjectStore`
         instance.
 
+        Example usage::
+
+            ctx.register_listing_table(
+                "snapshot",
+                [
+                    "s3://bucket/events/2024-01-01/part-0.csv",
+                    "s3://bucket/events/2024-01-02/part-3.csv",
+                ],
+                schema=schema,
+                file_sort_order=[[col("ts").sort()]],
+                file_format="csv",
+                format_options={"has_header": True, "delimiter": ";"},
+            )
+
         Args:
             name: Name of the resultant table.
-            path: Path to the file to register.
+            path: Path to the files to register, or a list of paths such as the
+                files listed in a manifest, which may be under different prefixes.
             table_partition_cols: Partition columns.
-            file_extension: File extension of the provided table.
-            schema: The data source schema.
+            file_extension: File extension of the provided table. By default the
+                files named in ``path`` are read whatever their extension, while
+                only the files with the extension of ``file_format`` are read
+                from directories.
+            schema: The data source schema. If None, the schema is inferred from
+                the files and merged across the paths.
             file_sort_order: Sort order for the file.
+            file_format: Format of the files: ``parquet``, ``csv``, ``json``,
+                ``arrow`` or ``avro``.
+            format_options: Options of the file format, as given in the
+                ``OPTIONS`` clause of ``CREATE EXTERNAL TABLE``, such as
+                ``has_header`` for CSV files. Options that are not set use the
+                configuration of the session.
         """
         if table_partition_cols is None:
             table_partition_cols = []
@@ -514,13 +568,22 @@ class SessionContext:
             if file_sort_order is not None
             else None
         )
+        path = [str(p) for p in path] if isinstance(path, list) else str(path)
+        if format_options is None:
+            format_options = {}
+        format_options = {
+            key: str(value).lower() if isinstance(value, bool) else str(value)
+            for key, value in format_options.items()
+        }
         self.ctx.register_listing_table(
             name,
-            str(path),
+            path,
             table_partition_cols,
             file_extension,
             schema,
             file_sort_order_raw,
+            file_format,
+            format_options,
         )
 
     def sql(self, query: str, options: SQLOptions | None = None) -> DataFrame:
@@ -542,6 +605,25 @@ class SessionContext:
             return DataFrame(self.ctx.sql(query))
         return DataFrame(self.ctx.sql_with_options(query, options.options_internal))
 
+    async def sql_async(
+        self, query: str, options: SQLOptions | None = None
+    ) -> DataFrame:
+        """Create a :py:class:`~datafusion.DataFrame` from SQL without blocking.
+
+        This is the ``asyncio`` counterpart of :py:func:`sql`. Statements with side
+        effects such as ``CREATE TABLE AS`` are executed on the DataFusion runtime
+        while the calling event loop remains free.
+
+        Args:
+            query: SQL query text.
+            options: If provided, the query will be validated against these options.
+
+        Returns:
+            DataFrame representation of the SQL query.
+        """
+        options_internal = None if options is None else options.options_internal
+        return DataFrame(await self.ctx.sql_async(query, options_internal))
+
     def sql_with_options(self, query: str, options: SQLOptions) -> DataFrame:
         """Create a :py:class:`~datafusion.dataframe.DataFrame` from SQL query text.
 
@@ -618,6 +700,7 @@ class SessionContext:
         self,
         data: ArrowStreamExportable | ArrowArrayExportable,
         name: str | None = None,
+        streaming: bool = False,
     ) -> DataFrame:
         """Create a :py:class:`~datafusion.dataframe.DataFrame` from an Arrow source.
 
@@ -630,11 +713,15 @@ class SessionContext:
         Args:
             data: Arrow data source.
             name: Name of the DataFrame.
+            streaming: If ``True``, the batches of an ``__arrow_c_stream__`` source
+                are read lazily while the DataFrame executes instead of being loaded
+                into memory up front. The resulting DataFrame can only be executed
+                once. See :py:func:`register_arrow_stream`.
 
         Returns:
             DataFrame representation of the Arrow table.
         """
-        return DataFrame(self.ctx.from_arrow(data, name))
+        return DataFrame(self.ctx.from_arrow(data, name, streaming))
 
     @deprecated("Use ``from_arrow`` instead.")
     def from_arrow_table(
@@ -670,15 +757,22 @@ class SessionContext:
         """
         return DataFrame(self.ctx.from_polars(data, name))
 
-    def register_table(self, name: str, table: Table) -> None:
+    def register_table(self, name: str, table: Table | TableProvider) -> None:
         """Register a :py:class: `~datafusion.catalog.Table` as a table.
 
         The registered table can be referenced from SQL statement executed against.
 
         Args:
             name: Name of the resultant table.
-            table: DataFusion table to add to the session context.
+            table: DataFusion table, Python
+                :py:class:`~datafusion.catalog.TableProvider` or an object
+                accepted by :py:meth:`~datafusion.catalog.Table.from_pycapsule`
+                to add to the session context.
         """
+        if isinstance(table, TableProvider):
+            table = Table.from_provider(table)
+        if isinstance(table, Table):
+            table = table.table
         self.ctx.register_table(name, table)
 
     def deregister_table(self, name: str) -> None:
@@ -699,6 +793,57 @@ class SessionContext:
         """
         self.ctx.register_record_batches(name, partitions)
 
+    def register_arrow_stream(
+        self, name: str, data: ArrowStreamExportable | ArrowArrayExportable
+    ) -> None:
+        """Register an Arrow source as a streaming table.
+
+        The source can be any object that implements ``__arrow_c_stream__``, such as
+        a Polars DataFrame, a DuckDB result or a :py:class:`pyarrow.RecordBatchReader`.
+        Its batches are not loaded into memory up front but read while a query
+        scans the table, so the table can only be scanned once. Objects that only
+        implement ``__arrow_c_array__`` are registered as an in-memory table.
+
+        Args:
+            name: Name of the resultant table.
+            data: Arrow data source.
+        """
+        self.ctx.register_arrow_stream(name, data)
+
+    def register_batch_iterator(
+        self,
+        name: str,
+        batches: Iterable[pyarrow.RecordBatch]
+        | Callable[[], Iterable[pyarrow.RecordBatch]],
+        schema: pyarrow.Schema,
+    ) -> None:
+        """Register a Python iterable of record batches as a streaming table.
+
+        Batches are pulled from ``batches`` one at a time while a query scans the
+        table, so data from sources such as a message queue consumer or a paginated
+        API never has to be buffered in memory as a whole.
+
+        If ``batches`` is a callable, it is invoked every time the table is scanned
+        and must return a new iterable, which allows the table to be queried more
+        than once. Any other iterable, e.g. a generator, can only be scanned once.
+
+        Args:
+            name: Name of the resultant table.
+            batches: Iterable of :py:class:`pyarrow.RecordBatch` or a callable that
+                returns one.
+            schema: Schema of every record batch produced by ``batches``.
+
+        Example usage::
+
+            def pages():
+                for page in range(3):
+                    yield pa.RecordBatch.from_pydict({"page": [page]})
+
+            schema = pa.schema([("page", pa.int64())])
+            ctx.register_batch_iterator("pages", pages, schema)
+        """
+        self.ctx.register_batch_iterator(name, batches, schema)
+
     def register_parquet(
         self,
         name: str,
@@ -709,6 +854,13 @@ class SessionContext:
         skip_metadata: bool = True,
         schema: pyarrow.Schema | None = None,
         file_sort_order: list[list[Expr]] | None = None,
+        enable_page_index: bool | None = None,
+        bloom_filter_on_read: bool | None = None,
+        pushdown_filters: bool | None = None,
+        reorder_filters: bool | None = None,
+        metadata_size_hint: int | None = None,
+        binary_as_string: bool | None = None,
+        schema_force_view_types: bool | None = None,
     ) -> None:
         """Register a Parquet file as a table.
 
@@ -728,6 +880,24 @@ class SessionContext:
                 conflicts due to metadata.
             schema: The data source schema.
             file_sort_order: Sort order for the file.
+            enable_page_index: Whether the page index is read and used to skip
+                pages not matching the predicate.
+            bloom_filter_on_read: Whether bloom filters are used to prune row
+                groups.
+            pushdown_filters: Whether filters are applied while decoding the
+                rows, so that only the matching rows are materialized.
+            reorder_filters: Whether pushed down filters are reordered to apply
+                the most selective and cheapest ones first.
+            metadata_size_hint: Number of bytes fetched at the end of each file
+                in the first request, which avoids a second request for the
+                metadata when it fits.
+            binary_as_string: Whether binary columns are read as strings, for
+                files written without the UTF-8 annotation.
+            schema_force_view_types: Whether string and binary columns are read
+                as ``string_view`` and ``binary_view``.
+
+            Options left to ``None`` use the ``datafusion.execution.parquet``
+            settings of the session.
         """
         if table_partition_cols is None:
             table_partition_cols = []
@@ -740,6 +910,13 @@ class SessionContext:
             skip_metadata,
             schema,
             file_sort_order,
+            enable_page_index,
+            bloom_filter_on_read,
+            pushdown_filters,
+            reorder_filters,
+            metadata_size_hint,
+            binary_as_string,
+            schema_force_view_types,
         )
 
     def register_csv(
@@ -752,6 +929,12 @@ class SessionContext:
         schema_infer_max_records: int = 1000,
         file_extension: str = ".csv",
         file_compression_type: str | None = None,
+        quote: str = '"',
+        escape: str | None = None,
+        comment: str | None = None,
+        terminator: str | None = None,
+        newlines_in_values: bool = False,
+        null_regex: str | None = None,
     ) -> None:
         """Register a CSV file as a table.
 
@@ -771,6 +954,18 @@ class SessionContext:
             file_extension: File extension; only files with this extension are
                 selected for data input.
             file_compression_type: File compression type.
+            quote: Character used to quote fields.
+            escape: Character used to escape quotes inside quoted fields. By
+                default quotes are escaped by doubling them.
+            comment: Lines starting with this character are skipped.
+            terminator: Character terminating the lines. By default lines end
+                with ``\\n`` or ``\\r\\n``.
+            newlines_in_values: Whether quoted values may contain newlines. This
+                prevents the files from being split into ranges read in parallel.
+            null_regex: Regular expression matching the values read as nulls in
+                addition to empty values, such as ``NA|-``. A value is null only
+                when the whole of it matches. The other values are parsed as in
+                a read without ``null_regex``.
         """
         if isinstance(path, list):
             path = [str(p) for p in path]
@@ -786,6 +981,12 @@ class SessionContext:
             schema_infer_max_records,
             file_extension,
             file_compression_type,
+            quote,
+            escape,
+            comment,
+            terminator,
+            newlines_in_values,
+            null_regex,
         )
 
     def register_json(
@@ -797,12 +998,23 @@ class SessionContext:
         file_extension: str = ".json",
         table_partition_cols: list[tuple[str, str]] | None = None,
         file_compression_type: str | None = None,
+        schema_hints: pyarrow.Schema | dict[str, pyarrow.DataType] | None = None,
+        json_format: str = "ndjson",
     ) -> None:
         """Register a JSON file as a table.
 
         The registered table can be referenced from SQL statement executed
         against this context.
 
+        Example usage::
+
+            ctx.register_json(
+                "events",
+                "events.json",
+                schema_hints={"ts": pa.timestamp("us")},
+                json_format="array",
+            )
+
         Args:
             name: Name of the table to register.
             path: Path to the JSON file.
@@ -813,6 +1025,14 @@ class SessionContext:
                 selected for data input.
             table_partition_cols: Partition columns.
             file_compression_type: File compression type.
+            schema_hints: Types of some of the fields, such as nested structs or
+                timestamps, which replace the inferred ones. Cannot be given
+                together with ``schema``.
+            json_format: ``ndjson`` for files with one JSON object per line, or
+                ``array`` for files holding a JSON array of objects. Array files
+                are not streamed: each file is parsed whole and all of them are
+                held in memory as a table, which takes several times the size of
+                the files. They do not support partition columns.
         """
         if table_partition_cols is None:
             table_partition_cols = []
@@ -824,6 +1044,8 @@ class SessionContext:
             file_extension,
             table_partition_cols,
             file_compression_type,
+            _schema_hints(schema_hints),
+            json_format,
         )
 
     def register_avro(
@@ -852,6 +1074,35 @@ class SessionContext:
             name, str(path), schema, file_extension, table_partition_cols
         )
 
+    def register_arrow(
+        self,
+        name: str,
+        path: str | pathlib.Path,
+        schema: pyarrow.Schema | None = None,
+        file_extension: str = ".arrow",
+        table_partition_cols: list[tuple[str, str]] | None = None,
+    ) -> None:
+        """Register an Arrow IPC file as a table.
+
+        The registered table can be referenced from SQL statement executed against
+        this context. Files must use the Arrow IPC file format, also known as
+        Feather V2. Files in the IPC stream format can be opened with
+        :py:func:`pyarrow.ipc.open_stream` and registered with
+        :py:meth:`register_arrow_stream` instead.
+
+        Args:
+            name: Name of the table to register.
+            path: Path to the Arrow IPC file.
+            schema: The data source schema.
+            file_extension: File extension to select.
+            table_partition_cols:  Partition columns.
+        """
+        if table_partition_cols is None:
+            table_partition_cols = []
+        self.ctx.register_arrow(
+            name, str(path), schema, file_extension, table_partition_cols
+        )
+
     def register_dataset(self, name: str, dataset: pyarrow.dataset.Dataset) -> None:
         """Register a :py:class:`pyarrow.dataset.Dataset` as a table.
 
@@ -865,6 +1116,10 @@ class SessionContext:
         """Register a user-defined function (UDF) with the context."""
         self.ctx.register_udf(udf._udf)
 
+    def register_udtf(self, func: TableFunction) -> None:
+        """Register a user-defined table function (UDTF) with the context."""
+        self.ctx.register_udtf(func._udtf)
+
     def register_udaf(self, udaf: AggregateUDF) -> None:
         """Register a user-defined aggregation function (UDAF) with the context."""
         self.ctx.register_udaf(udaf._udaf)
@@ -877,6 +1132,31 @@ class SessionContext:
         """Retrieve a catalog by name."""
         return self.ctx.catalog(name)
 
+    def register_catalog(self, name: str, catalog: Catalog | CatalogProvider) -> None:
+        """Register a catalog with the given ``name``.
+
+        Tables in the catalog can be referenced from SQL as
+        ``catalog.database.table``.
+
+        Args:
+            name: Name of the catalog.
+            catalog: DataFusion catalog or Python
+                :py:class:`~datafusion.catalog.CatalogProvider` to register.
+        """
+        self.ctx.register_catalog(name, catalog)
+
+    def register_schema(self, name: str, schema: Database | SchemaProvider) -> None:
+        """Register a database with the given ``name`` in the default catalog.
+
+        Tables in the database can be referenced from SQL as ``database.table``.
+
+        Args:
+            name: Name of the database.
+            schema: DataFusion database or Python
+                :py:class:`~datafusion.catalog.SchemaProvider` to register.
+        """
+        self.ctx.register_schema(name, schema)
+
     @deprecated(
         "Use the catalog provider interface ``SessionContext.Catalog`` to "
         "examine available catalogs, schemas and tables"
@@ -909,8 +1189,10 @@ class SessionContext:
         file_extension: str = ".json",
         table_partition_cols: list[tuple[str, str]] | None = None,
         file_compression_type: str | None = None,
+        schema_hints: pyarrow.Schema | dict[str, pyarrow.DataType] | None = None,
+        json_format: str = "ndjson",
     ) -> DataFrame:
-        """Read a line-delimited JSON data source.
+        """Read a JSON data source.
 
         Args:
             path: Path to the JSON file.
@@ -921,6 +1203,14 @@ class SessionContext:
                 selected for data input.
             table_partition_cols: Partition columns.
             file_compression_type: File compression type.
+            schema_hints: Types of some of the fields, such as nested structs or
+                timestamps, which replace the inferred ones. Cannot be given
+                together with ``schema``.
+            json_format: ``ndjson`` for files with one JSON object per line, or
+                ``array`` for files holding a JSON array of objects. Array files
+                are not streamed: each file is parsed whole and all of them are
+                held in memory as a table, which takes several times the size of
+                the files. They do not support partition columns.
 
         Returns:
             DataFrame representation of the read JSON files.
@@ -935,6 +1225,8 @@ class SessionContext:
                 file_extension,
                 table_partition_cols,
                 file_compression_type,
+                _schema_hints(schema_hints),
+                json_format,
             )
         )
 
@@ -948,6 +1240,12 @@ class SessionContext:
         file_extension: str = ".csv",
         table_partition_cols: list[tuple[str, str]] | None = None,
         file_compression_type: str | None = None,
+        quote: str = '"',
+        escape: str | None = None,
+        comment: str | None = None,
+        terminator: str | None = None,
+        newlines_in_values: bool = False,
+        null_regex: str | None = None,
     ) -> DataFrame:
         """Read a CSV data source.
 
@@ -965,6 +1263,18 @@ class SessionContext:
                 selected for data input.
             table_partition_cols:  Partition columns.
             file_compression_type:  File compression type.
+            quote: Character used to quote fields.
+            escape: Character used to escape quotes inside quoted fields. By
+                default quotes are escaped by doubling them.
+            comment: Lines starting with this character are skipped.
+            terminator: Character terminating the lines. By default lines end
+                with ``\\n`` or ``\\r\\n``.
+            newlines_in_values: Whether quoted values may contain newlines. This
+                prevents the files from being split into ranges read in parallel.
+            null_regex: Regular expression matching the values read as nulls in
+                addition to empty values, such as ``NA|-``. A value is null only
+                when the whole of it matches. The other values are parsed as in
+                a read without ``null_regex``.
 
         Returns:
             DataFrame representation of the read CSV files
@@ -984,6 +1294,12 @@ class SessionContext:
                 file_extension,
                 table_partition_cols,
                 file_compression_type,
+                quote,
+                escape,
+                comment,
+                terminator,
+                newlines_in_values,
+                null_regex,
             )
         )
 
@@ -996,6 +1312,13 @@ class SessionContext:
         skip_metadata: bool = True,
         schema: pyarrow.Schema | None = None,
         file_sort_order: list[list[Expr]] | None = None,
+        enable_page_index: bool | None = None,
+        bloom_filter_on_read: bool | None = None,
+        pushdown_filters: bool | None = None,
+        reorder_filters: bool | None = None,
+        metadata_size_hint: int | None = None,
+        binary_as_string: bool | None = None,
+        schema_force_view_types: bool | None = None,
     ) -> DataFrame:
         """Read a Parquet source into a :py:class:`~datafusion.dataframe.Dataframe`.
 
@@ -1013,6 +1336,24 @@ class SessionContext:
                 the parquet reader will try to infer it based on data in the
                 file.
             file_sort_order: Sort order for the file.
+            enable_page_index: Whether the page index is read and used to skip
+                pages not matching the predicate.
+            bloom_filter_on_read: Whether bloom filters are used to prune row
+                groups.
+            pushdown_filters: Whether filters are applied while decoding the
+                rows, so that only the matching rows are materialized.
+            reorder_filters: Whether pushed down filters are reordered to apply
+                the most selective and cheapest ones first.
+            metadata_size_hint: Number of bytes fetched at the end of each file
+                in the first request, which avoids a second request for the
+                metadata when it fits.
+            binary_as_string: Whether binary columns are read as strings, for
+                files written without the UTF-8 annotation.
+            schema_force_view_types: Whether string and binary columns are read
+                as ``string_view`` and ``binary_view``.
+
+            Options left to ``None`` use the ``datafusion.execution.parquet``
+            settings of the session.
 
         Returns:
             DataFrame representation of the read Parquet files
@@ -1028,6 +1369,13 @@ class SessionContext:
                 skip_metadata,
                 schema,
                 file_sort_order,
+                enable_page_index,
+                bloom_filter_on_read,
+                pushdown_filters,
+                reorder_filters,
+                metadata_size_hint,
+                binary_as_string,
+                schema_force_view_types,
             )
         )
 
@@ -1055,6 +1403,32 @@ class SessionContext:
             self.ctx.read_avro(str(path), schema, file_partition_cols, file_extension)
         )
 
+    def read_arrow(
+        self,
+        path: str | pathlib.Path,
+        schema: pyarrow.Schema | None = None,
+        file_partition_cols: list[tuple[str, str]] | None = None,
+        file_extension: str = ".arrow",
+    ) -> DataFrame:
+        """Create a :py:class:`DataFrame` for reading an Arrow IPC data source.
+
+        Files must use the Arrow IPC file format, also known as Feather V2.
+
+        Args:
+            path: Path to the Arrow IPC file.
+            schema: The data source schema.
+            file_partition_cols: Partition columns.
+            file_extension: File extension to select.
+
+        Returns:
+            DataFrame representation of the read Arrow IPC file
+        """
+        if file_partition_cols is None:
+            file_partition_cols = []
+        return DataFrame(
+            self.ctx.read_arrow(str(path), schema, file_partition_cols, file_extension)
+        )
+
     def read_table(self, table: Table) -> DataFrame:
         """Creates a :py:class:`~datafusion.dataframe.DataFrame` from a table.
 
@@ -1067,3 +1441,14 @@ class SessionContext:
     def execute(self, plan: ExecutionPlan, partitions: int) -> RecordBatchStream:
         """Execute the ``plan`` and return the results."""
         return RecordBatchStream(self.ctx.execute(plan._raw_plan, partitions))
+
+
+def _schema_hints(
+    schema_hints: pyarrow.Schema | dict[str, pyarrow.DataType] | None,
+) -> pyarrow.Schema | None:
+    """Convert JSON schema hints given as a mapping of field types into a schema."""
+    if isinstance(schema_hints, dict):
+        import pyarrow as pa
+
+        return pa.schema(list(schema_hints.items()))
+    return schema_hints
@@ -33,10 +33,181 @@ if TYPE_CHECKING:
     import pathlib
     from typing import Callable, Sequence
 
+    from datafusion._internal import CancellationToken
+
 from datafusion._internal import DataFrame as DataFrameInternal
 from datafusion.expr import Expr, SortExpr, sort_or_default
 
 
+def _write_options(
+    partition_by: list[str] | None,
+    single_file_output: bool | None,
+    overwrite: bool,
+    sort_by: list[Expr | SortExpr] | None,
+) -> dict[str, Any]:
+    """Collect the options shared by the ``write_*`` methods of a DataFrame."""
+    return {
+        "partition_by": partition_by or [],
+        "single_file_output": single_file_output,
+        "overwrite": overwrite,
+        "sort_by": [sort_or_default(e) for e in sort_by or []],
+    }
+
+
+def _config_value(value: Any) -> str:
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    return str(value)
+
+
+class ParquetColumnOptions:
+    """Parquet writer options for a single column.
+
+    Options that are not set use the value of the :py:class:`ParquetWriterOptions`.
+    """
+
+    def __init__(
+        self,
+        encoding: str | None = None,
+        dictionary_enabled: bool | None = None,
+        compression: str | None = None,
+        statistics_enabled: str | None = None,
+        bloom_filter_enabled: bool | None = None,
+        bloom_filter_fpp: float | None = None,
+        bloom_filter_ndv: int | None = None,
+        max_statistics_size: int | None = None,
+    ) -> None:
+        """Create the parquet writer options of a column.
+
+        Args:
+            encoding: Encoding of the column, such as ``plain`` or
+                ``delta_binary_packed``.
+            dictionary_enabled: Whether dictionary encoding is used.
+            compression: Compression codec, such as ``snappy`` or ``zstd(3)``.
+            statistics_enabled: Level of statistics to write: ``none``, ``chunk``
+                or ``page``. ``page`` statistics produce a page index.
+            bloom_filter_enabled: Whether a bloom filter is written.
+            bloom_filter_fpp: False positive probability of the bloom filter.
+            bloom_filter_ndv: Expected number of distinct values of the bloom filter.
+            max_statistics_size: Maximum size of the statistics in bytes.
+        """
+        self.encoding = encoding
+        self.dictionary_enabled = dictionary_enabled
+        self.compression = compression
+        self.statistics_enabled = statistics_enabled
+        self.bloom_filter_enabled = bloom_filter_enabled
+        self.bloom_filter_fpp = bloom_filter_fpp
+        self.bloom_filter_ndv = bloom_filter_ndv
+        self.max_statistics_size = max_statistics_size
+
+
+class ParquetWriterOptions:
+    """Options used when writing parquet files.
+
+    Options that are not set use the DataFusion defaults. See the ``format``
+    options of ``COPY TO`` in the DataFusion documentation for details.
+    """
+
+    def __init__(
+        self,
+        data_pagesize_limit: int | None = None,
+        write_batch_size: int | None = None,
+        writer_version: str | None = None,
+        compression: str | None = None,
+        dictionary_enabled: bool | None = None,
+        dictionary_page_size_limit: int | None = None,
+        statistics_enabled: str | None = None,
+        max_statistics_size: int | None = None,
+        max_row_group_size: int | None = None,
+        created_by: str | None = None,
+        column_index_truncate_length: int | None = None,
+        data_page_row_count_limit: int | None = None,
+        encoding: str | None = None,
+        bloom_filter_on_write: bool | None = None,
+        bloom_filter_fpp: float | None = None,
+        bloom_filter_ndv: int | None = None,
+        allow_single_file_parallelism: bool | None = None,
+        maximum_parallel_row_group_writers: int | None = None,
+        maximum_buffered_record_batches_per_stream: int | None = None,
+        key_value_metadata: dict[str, str] | None = None,
+        column_specific_options: dict[str, ParquetColumnOptions] | None = None,
+    ) -> None:
+        """Create the parquet writer options.
+
+        Args:
+            data_pagesize_limit: Best effort maximum size of a data page in bytes.
+            write_batch_size: Number of rows written to a column chunk at a time.
+            writer_version: Parquet writer version, ``1.0`` or ``2.0``.
+            compression: Compression codec, such as ``snappy`` or ``zstd(3)``.
+            dictionary_enabled: Whether dictionary encoding is used.
+            dictionary_page_size_limit: Best effort maximum size of a dictionary
+                page in bytes.
+            statistics_enabled: Level of statistics to write: ``none``, ``chunk``
+                or ``page``. ``page`` statistics produce a page index.
+            max_statistics_size: Maximum size of the statistics in bytes.
+            max_row_group_size: Maximum number of rows in a row group.
+            created_by: Value of the ``created_by`` file metadata.
+            column_index_truncate_length: Length at which the min and max values
+                of the column index are truncated.
+            data_page_row_count_limit: Best effort maximum number of rows in a
+                data page.
+            encoding: Encoding of the columns, such as ``plain`` or
+                ``delta_binary_packed``.
+            bloom_filter_on_write: Whether bloom filters are written.
+            bloom_filter_fpp: False positive probability of the bloom filters.
+            bloom_filter_ndv: Expected number of distinct values of the bloom
+                filters.
+            allow_single_file_parallelism: Whether a single file is written with
+                parallel row group writers.
+            maximum_parallel_row_group_writers: Number of row groups written in
+                parallel.
+            maximum_buffered_record_batches_per_stream: Number of record batches
+                buffered for every parallel column writer.
+            key_value_metadata: Key-value metadata added to the file.
+            column_specific_options: Options overriding the above for individual
+                columns, by column name.
+        """
+        self.data_pagesize_limit = data_pagesize_limit
+        self.write_batch_size = write_batch_size
+        self.writer_version = writer_version
+        self.compression = compression
+        self.dictionary_enabled = dictionary_enabled
+        self.dictionary_page_size_limit = dictionary_page_size_limit
+        self.statistics_enabled = statistics_enabled
+        self.max_statistics_size = max_statistics_size
+        self.max_row_group_size = max_row_group_size
+        self.created_by = created_by
+        self.column_index_truncate_length = column_index_truncate_length
+        self.data_page_row_count_limit = data_page_row_count_limit
+        self.encoding = encoding
+        self.bloom_filter_on_write = bloom_filter_on_write
+        self.bloom_filter_fpp = bloom_filter_fpp
+        self.bloom_filter_ndv = bloom_filter_ndv
+        self.allow_single_file_parallelism = allow_single_file_parallelism
+        self.maximum_parallel_row_group_writers = maximum_parallel_row_group_writers
+        self.maximum_buffered_record_batches_per_stream = (
+            maximum_buffered_record_batches_per_stream
+        )
+        self.key_value_metadata = key_value_metadata or {}
+        self.column_specific_options = column_specific_options or {}
+
+    def _to_config(self) -> dict[str, str]:
+        """Convert the options into the ``format.*`` options of ``COPY TO``."""
+        config = {
+            key: _config_value(value)
+            for key, value in vars(self).items()
+            if key not in ("key_value_metadata", "column_specific_options")
+            and value is not None
+        }
+        for key, value in self.key_value_metadata.items():
+            config[f"metadata::{key}"] = value
+        for column, options in self.column_specific_options.items():
+            for key, value in vars(options).items():
+                if value is not None:
+                    config[f"{key}::{column}"] = _config_value(value)
+        return config
+
+
 class DataFrame:
     """Two dimensional table representation of data.
 
@@ -319,17 +490,41 @@ class DataFrame:
         """
         return DataFrame(self.df.limit(n, max(0, self.count() - n)))
 
-    def collect(self) -> list[pa.RecordBatch]:
+    def collect(
+        self,
+        timeout: float | None = None,
+        token: CancellationToken | None = None,
+    ) -> list[pa.RecordBatch]:
         """Execute this :py:class:`DataFrame` and collect results into memory.
 
         Prior to calling ``collect``, modifying a DataFrme simply updates a plan
         (no actual computation is performed). Calling ``collect`` triggers the
         computation.
 
+        A running query can always be interrupted with ``KeyboardInterrupt``. If the
+        ``timeout`` elapses or the ``token`` is cancelled first, the query is
+        aborted and :py:class:`~datafusion.QueryCancelledError` is raised.
+
+        Args:
+            timeout: Maximum number of seconds to let the query run.
+            token: Handle used to cancel the query from another thread.
+
+        Returns:
+            List of :py:class:`pyarrow.RecordBatch` collected from the DataFrame.
+        """
+        return self.df.collect(timeout, token)
+
+    async def collect_async(self) -> list[pa.RecordBatch]:
+        """Execute this :py:class:`DataFrame` without blocking the event loop.
+
+        This is the ``asyncio`` counterpart of :py:func:`collect`. The query runs on
+        the DataFusion runtime while the calling event loop remains free to make
+        progress on other tasks.
+
         Returns:
             List of :py:class:`pyarrow.RecordBatch` collected from the DataFrame.
         """
-        return self.df.collect()
+        return await self.df.collect_async()
 
     def cache(self) -> DataFrame:
         """Cache the DataFrame as a memory table.
@@ -339,17 +534,25 @@ class DataFrame:
         """
         return DataFrame(self.df.cache())
 
-    def collect_partitioned(self) -> list[list[pa.RecordBatch]]:
+    def collect_partitioned(
+        self,
+        timeout: float | None = None,
+        token: CancellationToken | None = None,
+    ) -> list[list[pa.RecordBatch]]:
         """Execute this DataFrame and collect all partitioned results.
 
         This operation returns :py:class:`pyarrow.RecordBatch` maintaining the input
         partitioning.
 
+        Args:
+            timeout: Maximum number of seconds to let the query run.
+            token: Handle used to cancel the query from another thread.
+
         Returns:
             List of list of :py:class:`RecordBatch` collected from the
                 DataFrame.
         """
-        return self.df.collect_partitioned()
+        return self.df.collect_partitioned(timeout, token)
 
     def show(self, num: int = 20) -> None:
         """Execute the DataFrame and print the result to the console.
@@ -608,37 +811,281 @@ class DataFrame:
         """
         return DataFrame(self.df.except_all(other.df))
 
-    def write_csv(self, path: str | pathlib.Path, with_header: bool = False) -> None:
+    def write_csv(
+        self,
+        path: str | pathlib.Path,
+        with_header: bool = False,
+        *,
+        delimiter: str = ",",
+        quote: str = '"',
+        escape: str | None = None,
+        double_quote: bool | None = None,
+        terminator: str | None = None,
+        null_value: str | None = None,
+        date_format: str | None = None,
+        datetime_format: str | None = None,
+        timestamp_format: str | None = None,
+        timestamp_tz_format: str | None = None,
+        time_format: str | None = None,
+        file_compression_type: str | None = None,
+        partition_by: list[str] | None = None,
+        single_file_output: bool | None = None,
+        overwrite: bool = False,
+        sort_by: list[Expr | SortExpr] | None = None,
+    ) -> None:
         """Execute the :py:class:`DataFrame`  and write the results to a CSV file.
 
+        Example usage::
+
+            df.write_csv(
+                "data.tsv",
+                with_header=True,
+                delimiter="\\t",
+                date_format="%d/%m/%Y",
+            )
+
         Args:
             path: Path of the CSV file to write.
             with_header: If true, output the CSV header row.
+            delimiter: Character separating the fields.
+            quote: Character used to quote fields.
+            escape: Character used to escape quotes. By default quotes are
+                escaped by doubling them.
+            double_quote: Whether quotes are escaped by doubling them.
+            terminator: Character terminating the lines, ``\\n`` by default.
+            null_value: Representation of null values, an empty string by default.
+            date_format: Format of date columns, using ``chrono`` format strings
+                such as ``%Y-%m-%d``.
+            datetime_format: Format of datetime columns.
+            timestamp_format: Format of timestamp columns without a time zone.
+            timestamp_tz_format: Format of timestamp columns with a time zone.
+            time_format: Format of time columns.
+            file_compression_type: Compression of the output: ``gzip``, ``bz2``,
+                ``xz`` or ``zstd``.
+            partition_by: Columns to partition the output by. The output is
+                written to a Hive-style directory tree such as ``col=value/``.
+            single_file_output: Whether to write a single file, which requires a
+                path with a file extension, or a directory of files. By default
+                a single file is written if the path has a file extension.
+            overwrite: Whether to replace the existing files at ``path`` instead of
+                adding new files next to them. Only files in the written format,
+                directly at ``path`` or in Hive partition directories, are
+                deleted, once the write succeeds, so the DataFrame may read from
+                ``path`` itself. The root of a file system or bucket can not be
+                overwritten.
+            sort_by: Sort order of the written rows.
         """
-        self.df.write_csv(str(path), with_header)
+        self.df.write_csv(
+            str(path),
+            with_header,
+            write_options=_write_options(
+                partition_by, single_file_output, overwrite, sort_by
+            ),
+            delimiter=delimiter,
+            quote=quote,
+            escape=escape,
+            double_quote=double_quote,
+            terminator=terminator,
+            null_value=null_value,
+            date_format=date_format,
+            datetime_format=datetime_format,
+            timestamp_format=timestamp_format,
+            timestamp_tz_format=timestamp_tz_format,
+            time_format=time_format,
+            file_compression_type=file_compression_type,
+        )
 
     def write_parquet(
         self,
         path: str | pathlib.Path,
         compression: str = "uncompressed",
         compression_level: int | None = None,
+        *,
+        partition_by: list[str] | None = None,
+        single_file_output: bool | None = None,
+        overwrite: bool = False,
+        sort_by: list[Expr | SortExpr] | None = None,
     ) -> None:
         """Execute the :py:class:`DataFrame` and write the results to a Parquet file.
 
+        Example usage::
+
+            df.write_parquet("s3://bucket/table/", partition_by=["date"])
+
         Args:
             path: Path of the Parquet file to write.
             compression: Compression type to use.
             compression_level: Compression level to use.
+            partition_by: Columns to partition the output by. The output is
+                written to a Hive-style directory tree such as ``col=value/``.
+            single_file_output: Whether to write a single file, which requires a
+                path with a file extension, or a directory of files. By default
+                a single file is written if the path has a file extension.
+            overwrite: Whether to replace the existing files at ``path`` instead of
+                adding new files next to them. Only files in the written format,
+                directly at ``path`` or in Hive partition directories, are
+                deleted, once the write succeeds, so the DataFrame may read from
+                ``path`` itself. The root of a file system or bucket can not be
+                overwritten.
+            sort_by: Sort order of the written rows.
         """
-        self.df.write_parquet(str(path), compression, compression_level)
+        self.df.write_parquet(
+            str(path),
+            compression,
+            compression_level,
+            _write_options(partition_by, single_file_output, overwrite, sort_by),
+        )
+
+    def write_parquet_with_options(
+        self,
+        path: str | pathlib.Path,
+        options: ParquetWriterOptions,
+        *,
+        partition_by: list[str] | None = None,
+        single_file_output: bool | None = None,
+        overwrite: bool = False,
+        sort_by: list[Expr | SortExpr] | None = None,
+    ) -> None:
+        """Execute the :py:class:`DataFrame` and write the results to a Parquet file.
+
+        Unlike :py:meth:`write_parquet`, every option of the parquet writer can be
+        set, globally or for individual columns.
+
+        Example usage::
+
+            options = ParquetWriterOptions(
+                max_row_group_size=100_000,
+                column_specific_options={
+                    "id": ParquetColumnOptions(bloom_filter_enabled=True),
+                },
+            )
+            df.write_parquet_with_options("data.parquet", options)
+
+        Args:
+            path: Path of the Parquet file to write.
+            options: Options of the parquet writer.
+            partition_by: Columns to partition the output by. The output is
+                written to a Hive-style directory tree such as ``col=value/``.
+            single_file_output: Whether to write a single file, which requires a
+                path with a file extension, or a directory of files. By default
+                a single file is written if the path has a file extension.
+            overwrite: Whether to replace the existing files at ``path`` instead of
+                adding new files next to them. Only files in the written format,
+                directly at ``path`` or in Hive partition directories, are
+                deleted, once the write succeeds, so the DataFrame may read from
+                ``path`` itself. The root of a file system or bucket can not be
+                overwritten.
+            sort_by: Sort order of the written rows.
+        """
+        self.df.write_parquet_with_options(
+            str(path),
+            options._to_config(),
+            _write_options(partition_by, single_file_output, overwrite, sort_by),
+        )
+
+    def write_arrow(
+        self,
+        path: str | pathlib.Path,
+        format: Literal["file", "stream"] = "file",
+        compression: Literal["lz4", "zstd"] | None = None,
+        *,
+        overwrite: bool = False,
+        sort_by: list[Expr | SortExpr] | None = None,
+    ) -> None:
+        """Execute the :py:class:`DataFrame` and write the results to an Arrow IPC file.
 
-    def write_json(self, path: str | pathlib.Path) -> None:
+        The results are always written to a single file.
+
+        Args:
+            path: Path of the Arrow IPC file to write.
+            format: ``file`` for the Arrow IPC file format, also known as Feather
+                V2, or ``stream`` for the Arrow IPC stream format.
+            compression: Compression of the record batches, ``lz4`` or ``zstd``.
+            overwrite: Whether to replace an existing file at ``path``.
+            sort_by: Sort order of the written rows.
+        """
+        self.df.write_arrow(
+            str(path),
+            format,
+            compression,
+            _write_options(None, None, overwrite, sort_by),
+        )
+
+    def write_json(
+        self,
+        path: str | pathlib.Path,
+        *,
+        file_compression_type: str | None = None,
+        date_format: str | None = None,
+        timestamp_format: str | None = None,
+        partition_by: list[str] | None = None,
+        single_file_output: bool | None = None,
+        overwrite: bool = False,
+        sort_by: list[Expr | SortExpr] | None = None,
+    ) -> None:
         """Execute the :py:class:`DataFrame` and write the results to a JSON file.
 
+        Each row is written as a JSON object on its own line.
+
+        Example usage::
+
+            df.write_json(
+                "events.json.gz",
+                file_compression_type="gzip",
+                timestamp_format="%Y-%m-%dT%H:%M:%S%.3f",
+            )
+
         Args:
             path: Path of the JSON file to write.
+            file_compression_type: Compression of the output: ``gzip``, ``bz2``,
+                ``xz`` or ``zstd``.
+            date_format: Format of top-level date columns, using ``chrono`` format
+                strings such as ``%Y-%m-%d``.
+            timestamp_format: Format of top-level timestamp columns. By default
+                timestamps are written in RFC 3339 format.
+            partition_by: Columns to partition the output by. The output is
+                written to a Hive-style directory tree such as ``col=value/``.
+            single_file_output: Whether to write a single file, which requires a
+                path with a file extension, or a directory of files. By default
+                a single file is written if the path has a file extension.
+            overwrite: Whether to replace the existing files at ``path`` instead of
+                adding new files next to them. Only files in the written format,
+                directly at ``path`` or in Hive partition directories, are
+                deleted, once the write succeeds, so the DataFrame may read from
+                ``path`` itself. The root of a file system or bucket can not be
+                overwritten.
+            sort_by: Sort order of the written rows.
+        """
+        self.df.write_json(
+            str(path),
+            _write_options(partition_by, single_file_output, overwrite, sort_by),
+            file_compression_type,
+            date_format,
+            timestamp_format,
+        )
+
+    def write_table(self, table_name: str, overwrite: bool = False) -> None:
+        """Execute the :py:class:`DataFrame` and insert the results into a table.
+
+        This is the counterpart of ``INSERT INTO`` for a table registered in the
+        session, such as a listing table created with
+        :py:meth:`~datafusion.context.SessionContext.register_listing_table` or a
+        memory table. The schema of the :py:class:`DataFrame` must match the one
+        of the table.
+
+        Example usage::
+
+            ctx.register_listing_table("events", "events/", schema=schema)
+            df.write_table("events")
+
+        Args:
+            table_name: Name of the table to insert into.
+            overwrite: Whether to replace the contents of the table instead of
+                appending to it. Listing tables are overwritten by deleting their
+                previous files once the new ones are written. Other tables raise
+                an error unless they support overwriting.
         """
-        self.df.write_json(str(path))
+        self.df.write_table(table_name, overwrite)
 
     def to_arrow_table(self) -> pa.Table:
         """Execute the :py:class:`DataFrame` and convert it into an Arrow Table.
@@ -648,21 +1095,49 @@ class DataFrame:
         """
         return self.df.to_arrow_table()
 
-    def execute_stream(self) -> RecordBatchStream:
+    def execute_stream(
+        self,
+        timeout: float | None = None,
+        token: CancellationToken | None = None,
+    ) -> RecordBatchStream:
         """Executes this DataFrame and returns a stream over a single partition.
 
+        Args:
+            timeout: Maximum number of seconds to let the query run, including the
+                time spent reading from the stream.
+            token: Handle used to cancel the query from another thread.
+
+        Returns:
+            Record Batch Stream over a single partition.
+        """
+        return RecordBatchStream(self.df.execute_stream(timeout, token))
+
+    async def execute_stream_async(self) -> RecordBatchStream:
+        """Executes this DataFrame without blocking the event loop.
+
+        The returned stream can be consumed with ``async for``.
+
         Returns:
             Record Batch Stream over a single partition.
         """
-        return RecordBatchStream(self.df.execute_stream())
+        return RecordBatchStream(await self.df.execute_stream_async())
 
-    def execute_stream_partitioned(self) -> list[RecordBatchStream]:
+    def execute_stream_partitioned(
+        self,
+        timeout: float | None = None,
+        token: CancellationToken | None = None,
+    ) -> list[RecordBatchStream]:
         """Executes this DataFrame and returns a stream for each partition.
 
+        Args:
+            timeout: Maximum number of seconds to let the query run, including the
+                time spent reading from the streams.
+            token: Handle used to cancel the query from another thread.
+
         Returns:
             One record batch stream per partition.
         """
-        streams = self.df.execute_stream_partitioned()
+        streams = self.df.execute_stream_partitioned(timeout, token)
         return [RecordBatchStream(rbs) for rbs in streams]
 
     def to_pandas(self) -> pd.DataFrame:
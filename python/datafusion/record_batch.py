@@ -74,3 +74,12 @@ class RecordBatchStream:
     def __iter__(self) -> typing_extensions.Self:
         """Iterator function."""
         return self
+
+    async def __anext__(self) -> RecordBatch:
+        """Async iterator function."""
+        next_batch = await self.rbs.__anext__()
+        return RecordBatch(next_batch)
+
+    def __aiter__(self) -> typing_extensions.Self:
+        """Async iterator function."""
+        return self
@@ -21,7 +21,7 @@ from __future__ import annotations
 
 import datafusion._internal as df_internal
 from datafusion.expr import Expr
-from typing import Callable, TYPE_CHECKING, TypeVar
+from typing import Any, Callable, TYPE_CHECKING, TypeVar
 from abc import ABCMeta, abstractmethod
 from typing import List, Optional
 from enum import Enum
@@ -86,9 +86,12 @@ class ScalarUDF:
         self,
         name: Optional[str],
         func: Callable[..., _R],
-        input_types: pyarrow.DataType | list[pyarrow.DataType],
-        return_type: _R,
+        input_types: pyarrow.DataType
+        | list[pyarrow.DataType]
+        | Callable[[list[pyarrow.DataType]], list[pyarrow.DataType]],
+        return_type: _R | Callable[[list[pyarrow.DataType]], _R],
         volatility: Volatility | str,
+        scalar_args: bool = False,
     ) -> None:
         """Instantiate a scalar user-defined function (UDF).
 
@@ -97,7 +100,7 @@ class ScalarUDF:
         if isinstance(input_types, pyarrow.DataType):
             input_types = [input_types]
         self._udf = df_internal.ScalarUDF(
-            name, func, input_types, return_type, str(volatility)
+            name, func, input_types, return_type, str(volatility), scalar_args
         )
 
     def __call__(self, *args: Expr) -> Expr:
@@ -109,24 +112,63 @@ class ScalarUDF:
         args_raw = [arg.expr for arg in args]
         return Expr(self._udf.__call__(*args_raw))
 
+    @staticmethod
+    def from_pycapsule(func: Any) -> ScalarUDF:
+        """Create a UDF implemented in Rust and compiled into a separate library.
+
+        ``func`` must either be a PyCapsule named ``datafusion_scalar_udf`` or an
+        object providing one from a ``__datafusion_scalar_udf__`` method. The
+        capsule holds the ``FFI_ScalarUDF`` C interface defined in the
+        ``datafusion_python::ffi`` Rust module. Such functions are evaluated
+        without acquiring the GIL. Capsules built for another version of
+        this interface are rejected with an error.
+
+        Example usage::
+
+            from my_rust_plugin import MyFunction
+
+            my_function = ScalarUDF.from_pycapsule(MyFunction())
+            ctx.register_udf(my_function)
+        """
+        udf = ScalarUDF.__new__(ScalarUDF)
+        udf._udf = df_internal.ScalarUDF.from_pycapsule(func)
+        return udf
+
+    def __datafusion_scalar_udf__(self) -> Any:
+        """Export this UDF in a PyCapsule to be used by other libraries."""
+        return self._udf.__datafusion_scalar_udf__()
+
     @staticmethod
     def udf(
         func: Callable[..., _R],
-        input_types: list[pyarrow.DataType],
-        return_type: _R,
+        input_types: list[pyarrow.DataType]
+        | Callable[[list[pyarrow.DataType]], list[pyarrow.DataType]],
+        return_type: _R | Callable[[list[pyarrow.DataType]], _R],
         volatility: Volatility | str,
         name: Optional[str] = None,
+        scalar_args: bool = False,
     ) -> ScalarUDF:
         """Create a new User-Defined Function.
 
         Args:
             func: A callable python function.
             input_types: The data types of the arguments to ``func``. This list
-                must be of the same length as the number of arguments.
+                must be of the same length as the number of arguments. Instead of a
+                list, a ``coerce_types`` callable can be given. It receives the
+                data types of the arguments of a call and returns the data types
+                they should be cast to before ``func`` is invoked, or raises if the
+                arguments are not supported. This allows calls with any number of
+                arguments.
             return_type: The data type of the return value from the python
-                function.
+                function, or a callable that computes the return type from the
+                (coerced) data types of the arguments.
             volatility: See ``Volatility`` for allowed values.
             name: A descriptive name for the function.
+            scalar_args: If ``True``, arguments that are constant for a batch, such
+                as literals, are passed to ``func`` as :py:class:`pyarrow.Scalar`
+                instead of being expanded into arrays of the batch length, and
+                ``func`` may return a :py:class:`pyarrow.Scalar` when the result is
+                the same for every row.
 
         Returns:
             A user-defined aggregate function, which can be used in either data
@@ -145,6 +187,7 @@ class ScalarUDF:
             input_types=input_types,
             return_type=return_type,
             volatility=volatility,
+            scalar_args=scalar_args,
         )
 
 
@@ -153,7 +196,13 @@ class Accumulator(metaclass=ABCMeta):
 
     @abstractmethod
     def state(self) -> List[pyarrow.Scalar]:
-        """Return the current state."""
+        """Return the current state.
+
+        One value must be returned for each of the ``state_type`` of the
+        :py:class:`AggregateUDF`. State values may be of nested types such as
+        lists, structs or maps. Plain python values are converted to the
+        corresponding state type.
+        """
         pass
 
     @abstractmethod
@@ -171,6 +220,87 @@ class Accumulator(metaclass=ABCMeta):
         """Return the resultant value."""
         pass
 
+    def size(self) -> Optional[int]:
+        """Return the approximate memory used by the accumulator in bytes.
+
+        The size is used to enforce the limits of the memory pool configured on
+        the :py:class:`~datafusion.context.RuntimeConfig`. Accumulators holding
+        a large state should override this method. Overrides are called after
+        every call to :py:meth:`update`, :py:meth:`merge` and
+        :py:meth:`retract_batch`, and exceptions they raise fail the query. When
+        this method is not overridden or returns ``None``, a fixed estimate
+        based on the state types is used.
+        """
+        return None
+
+    def supports_retract_batch(self) -> bool:
+        """Return whether :py:meth:`retract_batch` is implemented.
+
+        Accumulators that support retraction can be used in window functions with
+        a sliding frame, such as ``ROWS BETWEEN 2 PRECEDING AND CURRENT ROW``.
+        """
+        return False
+
+    def retract_batch(self, *values: pyarrow.Array) -> None:
+        """Remove values that have left the window frame from the state."""
+        raise NotImplementedError(
+            f"{self.__class__.__qualname__} does not implement retract_batch"
+        )
+
+
+class GroupsAccumulator(metaclass=ABCMeta):
+    """Accumulates the values of every group of an :py:class:`AggregateUDF` at once.
+
+    Unlike an :py:class:`Accumulator`, which is created once per group, a single
+    ``GroupsAccumulator`` receives the values for all groups together with a
+    ``group_indices`` array that assigns each row to a group. This allows the
+    aggregation to be vectorized across groups.
+
+    ``emit_to`` is ``None`` when the values of all groups should be returned, or
+    an integer ``n`` when only the first ``n`` groups should be returned. Emitted
+    groups are removed and the remaining groups are shifted down by ``n``.
+    """
+
+    @abstractmethod
+    def update_batch(
+        self,
+        values: List[pyarrow.Array],
+        group_indices: pyarrow.UInt64Array,
+        opt_filter: Optional[pyarrow.BooleanArray],
+        total_num_groups: int,
+    ) -> None:
+        """Update the state of each group in ``group_indices`` with ``values``."""
+        pass
+
+    @abstractmethod
+    def merge_batch(
+        self,
+        values: List[pyarrow.Array],
+        group_indices: pyarrow.UInt64Array,
+        opt_filter: Optional[pyarrow.BooleanArray],
+        total_num_groups: int,
+    ) -> None:
+        """Merge intermediate states produced by :py:meth:`state`."""
+        pass
+
+    @abstractmethod
+    def evaluate(self, emit_to: Optional[int]) -> pyarrow.Array:
+        """Return the final value of each group."""
+        pass
+
+    @abstractmethod
+    def state(self, emit_to: Optional[int]) -> List[pyarrow.Array]:
+        """Return the intermediate state of each group."""
+        pass
+
+    def size(self) -> int:
+        """Return the approximate memory used by the accumulator in bytes.
+
+        Overrides are called after every call that updates or emits the state
+        of the groups, and exceptions they raise fail the query.
+        """
+        return 0
+
 
 class AggregateUDF:
     """Class for performing scalar user-defined functions (UDF).
@@ -187,6 +317,8 @@ class AggregateUDF:
         return_type: pyarrow.DataType,
         state_type: list[pyarrow.DataType],
         volatility: Volatility | str,
+        groups_accumulator: Optional[Callable[[], GroupsAccumulator]] = None,
+        order_sensitive: bool = False,
     ) -> None:
         """Instantiate a user-defined aggregate function (UDAF).
 
@@ -200,6 +332,8 @@ class AggregateUDF:
             return_type,
             state_type,
             str(volatility),
+            groups_accumulator,
+            order_sensitive,
         )
 
     def __call__(self, *args: Expr) -> Expr:
@@ -211,6 +345,33 @@ class AggregateUDF:
         args_raw = [arg.expr for arg in args]
         return Expr(self._udaf.__call__(*args_raw))
 
+    @staticmethod
+    def from_pycapsule(func: Any) -> AggregateUDF:
+        """Create a UDAF implemented in Rust and compiled into a separate library.
+
+        ``func`` must either be a PyCapsule named ``datafusion_aggregate_udf`` or
+        an object providing one from a ``__datafusion_aggregate_udf__`` method.
+        The capsule holds the ``FFI_AggregateUDF`` C interface defined in the
+        ``datafusion_python::ffi`` Rust module. Such functions are evaluated
+        without acquiring the GIL. The ordering of an ordered aggregate call is
+        not passed to the function. Capsules built for another version of
+        this interface are rejected with an error.
+
+        Example usage::
+
+            from my_rust_plugin import MyAggregate
+
+            my_aggregate = AggregateUDF.from_pycapsule(MyAggregate())
+            ctx.register_udaf(my_aggregate)
+        """
+        udaf = AggregateUDF.__new__(AggregateUDF)
+        udaf._udaf = df_internal.AggregateUDF.from_pycapsule(func)
+        return udaf
+
+    def __datafusion_aggregate_udf__(self) -> Any:
+        """Export this UDAF in a PyCapsule to be used by other libraries."""
+        return self._udaf.__datafusion_aggregate_udf__()
+
     @staticmethod
     def udaf(
         accum: Callable[[], Accumulator],
@@ -219,6 +380,8 @@ class AggregateUDF:
         state_type: list[pyarrow.DataType],
         volatility: Volatility | str,
         name: Optional[str] = None,
+        groups_accumulator: Optional[Callable[[], GroupsAccumulator]] = None,
+        order_sensitive: bool = False,
     ) -> AggregateUDF:
         """Create a new User-Defined Aggregate Function.
 
@@ -256,13 +419,30 @@ class AggregateUDF:
             udaf2 = udaf(sum_bias_10, pa.float64(), pa.float64(), [pa.float64()], "immutable")
             udaf3 = udaf(lambda: Summarize(20.0), pa.float64(), pa.float64(), [pa.float64()], "immutable")
 
+        A :py:class:`GroupsAccumulator` factory may additionally be provided. It is
+        used instead of ``accum`` when aggregating with a ``GROUP BY`` so that all
+        groups are updated in a single call. ``accum`` is still used for aggregates
+        without grouping and for ``DISTINCT`` aggregates.
+
+        By default the ``ORDER BY`` of an aggregate, such as
+        ``my_udaf(a ORDER BY b)`` or ``my_udaf(col("a")).order_by(col("b")).build()``,
+        is ignored. Setting ``order_sensitive`` makes the planner sort the input of
+        the accumulator instead, and the values of the ordering expressions are
+        passed to :py:meth:`Accumulator.update` after the arguments. Since partial
+        aggregates of different partitions are merged in an arbitrary order, an
+        order sensitive accumulator should keep the ordering values in its state.
+
         Args:
             accum: The accumulator python function.
             input_types: The data types of the arguments to ``accum``.
             return_type: The data type of the return value.
-            state_type: The data types of the intermediate accumulation.
+            state_type: The data types of the intermediate accumulation. Nested
+                types such as lists and structs are supported.
             volatility: See :py:class:`Volatility` for allowed values.
             name: A descriptive name for the function.
+            groups_accumulator: Optional factory for a :py:class:`GroupsAccumulator`.
+            order_sensitive: Whether the accumulator requires its input ordered by
+                the ``ORDER BY`` of the aggregate.
 
         Returns:
             A user-defined aggregate function, which can be used in either data
@@ -276,6 +456,13 @@ class AggregateUDF:
             )
         if name is None:
             name = accum.__call__().__class__.__qualname__.lower()
+        if groups_accumulator is not None and not isinstance(
+            groups_accumulator.__call__(), GroupsAccumulator
+        ):
+            raise TypeError(
+                "Groups accumulator must implement the abstract base class "
+                "GroupsAccumulator"
+            )
         if isinstance(input_types, pyarrow.DataType):
             input_types = [input_types]
         return AggregateUDF(
@@ -285,6 +472,8 @@ class AggregateUDF:
             return_type=return_type,
             state_type=state_type,
             volatility=volatility,
+            groups_accumulator=groups_accumulator,
+            order_sensitive=order_sensitive,
         )
 
 
@@ -485,6 +674,32 @@ class WindowUDF:
         args_raw = [arg.expr for arg in args]
         return Expr(self._udwf.__call__(*args_raw))
 
+    @staticmethod
+    def from_pycapsule(func: Any) -> WindowUDF:
+        """Create a UDWF implemented in Rust and compiled into a separate library.
+
+        ``func`` must either be a PyCapsule named ``datafusion_window_udf`` or an
+        object providing one from a ``__datafusion_window_udf__`` method. The
+        capsule holds the ``FFI_WindowUDF`` C interface defined in the
+        ``datafusion_python::ffi`` Rust module. Such functions are evaluated
+        without acquiring the GIL. Capsules built for another version of
+        this interface are rejected with an error.
+
+        Example usage::
+
+            from my_rust_plugin import MyWindowFunction
+
+            my_window_function = WindowUDF.from_pycapsule(MyWindowFunction())
+            ctx.register_udwf(my_window_function)
+        """
+        udwf = WindowUDF.__new__(WindowUDF)
+        udwf._udwf = df_internal.WindowUDF.from_pycapsule(func)
+        return udwf
+
+    def __datafusion_window_udf__(self) -> Any:
+        """Export this UDWF in a PyCapsule to be used by other libraries."""
+        return self._udwf.__datafusion_window_udf__()
+
     @staticmethod
     def udwf(
         func: Callable[[], WindowEvaluator],
@@ -547,3 +762,61 @@ class WindowUDF:
             return_type=return_type,
             volatility=volatility,
         )
+
+
+class TableFunction:
+    """Class for performing user-defined table functions (UDTF).
+
+    Table functions are called in the ``FROM`` clause of a SQL query, e.g.
+    ``SELECT * FROM my_function(1, 'a')``, and produce a table from their arguments.
+    """
+
+    def __init__(self, name: str, func: Callable[..., Any]) -> None:
+        """Instantiate a user-defined table function (UDTF).
+
+        See :py:func:`udtf` for a convenience function and argument descriptions.
+        """
+        self._udtf = df_internal.TableFunction(name, func)
+
+    @staticmethod
+    def udtf(func: Callable[..., Any], name: Optional[str] = None) -> TableFunction:
+        """Create a new User-Defined Table Function.
+
+        ``func`` is called while the query is planned with one
+        :py:class:`~datafusion.expr.Expr` per argument of the call. Arguments are
+        typically literals, whose value can be obtained with
+        ``expr.python_value().as_py()``. It must return one of:
+
+        - a list of :py:class:`pyarrow.RecordBatch`
+        - an object implementing ``__arrow_c_stream__``, which is read lazily
+        - a :py:class:`~datafusion.catalog.Table` or an implementation of
+          :py:class:`~datafusion.catalog.TableProvider`
+
+        .. code-block:: python
+
+            import pyarrow as pa
+
+            def repeat(value, count):
+                value = value.python_value().as_py()
+                count = count.python_value().as_py()
+                return [pa.RecordBatch.from_pydict({"v": [value] * count})]
+
+            ctx.register_udtf(udtf(repeat))
+            ctx.sql("SELECT * FROM repeat('a', 3)")
+
+        Args:
+            func: A callable that produces a table from the call arguments.
+            name: A descriptive name for the function.
+
+        Returns:
+            A user-defined table function that can be registered with
+            :py:func:`~datafusion.context.SessionContext.register_udtf`.
+        """
+        if not callable(func):
+            raise TypeError("`func` must be callable.")
+        if name is None:
+            if hasattr(func, "__qualname__"):
+                name = func.__qualname__.lower()
+            else:
+                name = func.__class__.__name__.lower()
+        return TableFunction(name, func)
@@ -15,9 +15,21 @@
 # specific language governing permissions and limitations
 # under the License.
 
+import sys
+
 import pyarrow as pa
 import pytest
 
+from datafusion import (
+    Catalog,
+    CatalogProvider,
+    Database,
+    SchemaProvider,
+    Table,
+    TableProvider,
+)
+from datafusion.catalog import TableProviderFilterPushDown
+
 
 def test_basic(ctx, database):
     with pytest.raises(KeyError):
@@ -38,3 +50,213 @@ def test_basic(ctx, database):
             pa.field("float", pa.float64(), nullable=True),
         ]
     )
+
+
+class NumbersProvider(TableProvider):
+    def __init__(self):
+        self.scans = []
+
+    def schema(self):
+        return pa.schema([("a", pa.int64()), ("b", pa.string())])
+
+    def scan(self, projection, filters, limit):
+        self.scans.append((projection, [str(f) for f in filters], limit))
+        batch = pa.RecordBatch.from_pydict(
+            {"a": [1, 2, 3], "b": ["x", "y", "z"]}, schema=self.schema()
+        )
+        if projection is not None:
+            batch = batch.select(projection)
+        return [batch]
+
+    def supports_filters_pushdown(self, filters):
+        return [TableProviderFilterPushDown.Inexact for _ in filters]
+
+    def statistics(self):
+        return {"num_rows": 3}
+
+
+def test_python_table_provider(ctx):
+    provider = NumbersProvider()
+    ctx.register_table("numbers", provider)
+
+    batches = ctx.sql("SELECT b FROM numbers WHERE a > 1").collect()
+    assert pa.Table.from_batches(batches).column("b").to_pylist() == ["y", "z"]
+
+    projection, filters, limit = provider.scans[-1]
+    assert projection == [0, 1]
+    assert len(filters) == 1
+    assert limit is None
+
+    table = ctx.catalog().database().table("numbers")
+    assert table.kind == "physical"
+    assert table.schema == provider.schema()
+
+
+def test_python_table_provider_limit(ctx):
+    provider = NumbersProvider()
+    ctx.register_table("numbers", Table.from_provider(provider))
+
+    batches = ctx.sql("SELECT a FROM numbers LIMIT 2").collect()
+    assert sum(batch.num_rows for batch in batches) == 2
+    projection, filters, limit = provider.scans[-1]
+    assert projection == [0]
+    assert filters == []
+    assert limit == 2
+
+
+class FailingStatisticsProvider(NumbersProvider):
+    def __init__(self):
+        super().__init__()
+        self.statistics_calls = 0
+
+    def statistics(self):
+        self.statistics_calls += 1
+        raise ValueError("statistics are unavailable")
+
+
+def test_python_table_provider_statistics_errors(ctx, monkeypatch):
+    unraisable = []
+    monkeypatch.setattr(sys, "unraisablehook", unraisable.append)
+    provider = FailingStatisticsProvider()
+    ctx.register_table("numbers", provider)
+
+    assert ctx.sql("SELECT a FROM numbers").count() == 3
+    assert provider.statistics_calls == 1
+    assert any(
+        "statistics are unavailable" in str(hook.exc_value) for hook in unraisable
+    )
+
+
+class TenantSchema(SchemaProvider):
+    def __init__(self, tables):
+        self.tables = tables
+        self.lookups = []
+
+    def table_names(self):
+        return list(self.tables)
+
+    def table(self, name):
+        self.lookups.append(name)
+        return self.tables.get(name)
+
+
+class TenantCatalog(CatalogProvider):
+    def __init__(self, schemas):
+        self.schemas = schemas
+
+    def schema_names(self):
+        return list(self.schemas)
+
+    def schema(self, name):
+        return self.schemas.get(name)
+
+
+def test_python_catalog_provider(ctx):
+    schema = TenantSchema({"numbers": NumbersProvider()})
+    ctx.register_catalog("metastore", TenantCatalog({"tenant_a": schema}))
+
+    catalog = ctx.catalog("metastore")
+    assert catalog.names() == ["tenant_a"]
+    assert catalog.database("tenant_a").names() == {"numbers"}
+
+    df = ctx.sql("SELECT a FROM metastore.tenant_a.numbers")
+    assert df.count() == 3
+    assert "numbers" in schema.lookups
+
+    with pytest.raises(Exception):
+        ctx.sql("SELECT * FROM metastore.tenant_a.missing").collect()
+
+
+class FailingSchema(TenantSchema):
+    def table(self, name):
+        raise ValueError(f"cannot load table {name}")
+
+
+class FailingCatalog(TenantCatalog):
+    def schema(self, name):
+        raise ValueError(f"cannot load schema {name}")
+
+
+def test_python_provider_errors(ctx, monkeypatch):
+    ctx.register_catalog("failing", FailingCatalog({}))
+    ctx.register_catalog("metastore", TenantCatalog({"tenant_a": FailingSchema({})}))
+
+    # errors of methods returning a result are raised from the query
+    with pytest.raises(Exception, match="cannot load table numbers"):
+        ctx.sql("SELECT * FROM metastore.tenant_a.numbers").collect()
+
+    # others are reported through sys.unraisablehook
+    unraisable = []
+    monkeypatch.setattr(sys, "unraisablehook", unraisable.append)
+    with pytest.raises(Exception):
+        ctx.sql("SELECT * FROM failing.tenant_a.numbers").collect()
+    assert any(
+        "cannot load schema tenant_a" in str(hook.exc_value) for hook in unraisable
+    )
+
+
+def test_python_schema_provider(ctx):
+    schema = TenantSchema({"numbers": NumbersProvider()})
+    ctx.register_schema("tenant_b", schema)
+
+    assert set(ctx.catalog().names()) == {"public", "tenant_b"}
+    batches = ctx.sql("SELECT a FROM tenant_b.numbers WHERE a = 2").collect()
+    assert batches[0].column(0).to_pylist() == [2]
+
+
+def test_memory_catalog_mutation(ctx, database):
+    csv_table = ctx.catalog().database().table("csv")
+
+    catalog = Catalog.memory_catalog()
+    catalog.register_schema("tenant_a", Database.memory_schema())
+    catalog.register_schema("tenant_b", Database.memory_schema())
+    ctx.register_catalog("tenants", catalog)
+
+    tenant_a = catalog.database("tenant_a")
+    tenant_a.register_table("sales", csv_table)
+    tenant_a.register_table("numbers", NumbersProvider())
+    assert tenant_a.names() == {"sales", "numbers"}
+    assert ctx.catalog("tenants").database("tenant_b").names() == set()
+
+    assert ctx.sql("SELECT * FROM tenants.tenant_a.sales").count() == 4
+    assert ctx.sql("SELECT * FROM tenants.tenant_a.numbers").count() == 3
+
+    tenant_a.deregister_table("numbers")
+    assert tenant_a.names() == {"sales"}
+
+    with pytest.raises(Exception):
+        catalog.deregister_schema("tenant_a", cascade=False)
+
+    catalog.deregister_schema("tenant_a")
+    assert catalog.names() == ["tenant_b"]
+
+
+def test_register_schema_on_default_catalog(ctx, database):
+    csv_table = ctx.catalog().database().table("csv")
+
+    ctx.catalog().register_schema("tenant_a", Database.memory_schema())
+    ctx.catalog().database("tenant_a").register_table("sales", csv_table)
+
+    assert ctx.sql("SELECT * FROM tenant_a.sales").count() == 4
+    assert ctx.sql("SELECT * FROM csv").count() == 4
+
+
+def test_table_pycapsule_round_trip(ctx, database):
+    table = ctx.catalog().database().table("csv")
+    ctx.register_table("csv_copy", Table.from_pycapsule(table))
+
+    class Plugin:
+        def __datafusion_table_provider__(self):
+            return table.__datafusion_table_provider__()
+
+    ctx.register_table("csv_plugin", Plugin())
+
+    expected = ctx.sql("SELECT * FROM csv ORDER BY int").collect()
+    for name in ["csv_copy", "csv_plugin"]:
+        batches = ctx.sql(f"SELECT * FROM {name} ORDER BY int").collect()
+        assert pa.Table.from_batches(batches) == pa.Table.from_batches(expected)
+
+    provider = Table.from_pycapsule(Table.from_provider(NumbersProvider()))
+    ctx.register_table("numbers", provider)
+    batches = ctx.sql("SELECT b FROM numbers WHERE a > 1").collect()
+    assert pa.Table.from_batches(batches).column("b").to_pylist() == ["y", "z"]
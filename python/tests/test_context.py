@@ -14,13 +14,19 @@
 # KIND, either express or implied.  See the License for the
 # specific language governing permissions and limitations
 # under the License.
+import asyncio
 import gzip
 import os
+import signal
+import threading
+import time
 import datetime as dt
+from decimal import Decimal
 import pathlib
 
 import pyarrow as pa
 import pyarrow.dataset as ds
+import pyarrow.parquet as pq
 import pytest
 
 from datafusion import (
@@ -76,6 +82,75 @@ def test_temporary_files(tmp_path, path_to_str):
     assert db is not None
 
 
+def test_runtime_config_worker_threads():
+    with pytest.raises(ValueError, match="greater than 0"):
+        RuntimeConfig().with_worker_threads(0)
+
+    # the runtime shared by all contexts is already running at this point
+    SessionContext().sql("SELECT 1").collect()
+    with pytest.raises(RuntimeError, match="already been started"):
+        SessionContext(runtime=RuntimeConfig().with_worker_threads(3))
+
+
+@pytest.mark.skipif(not hasattr(os, "fork"), reason="requires os.fork")
+def test_execute_after_fork():
+    ctx = SessionContext()
+    assert ctx.sql("SELECT 1 AS a").to_pydict() == {"a": [1]}
+
+    pid = os.fork()
+    if pid == 0:
+        # the child needs a runtime of its own to make progress
+        try:
+            result = ctx.sql("SELECT 2 AS a").to_pydict()
+            os._exit(0 if result == {"a": [2]} else 1)
+        except BaseException:
+            os._exit(2)
+
+    _, status = os.waitpid(pid, 0)
+    assert os.WIFEXITED(status) and os.WEXITSTATUS(status) == 0
+    assert ctx.sql("SELECT 3 AS a").to_pydict() == {"a": [3]}
+
+
+@pytest.mark.skipif(not hasattr(os, "fork"), reason="requires os.fork")
+def test_execute_after_fork_while_other_threads_execute():
+    ctx = SessionContext()
+    stop = threading.Event()
+
+    def run_queries():
+        while not stop.is_set():
+            ctx.sql("SELECT 1").collect()
+
+    threads = [threading.Thread(target=run_queries) for _ in range(4)]
+    for thread in threads:
+        thread.start()
+    try:
+        for _ in range(5):
+            pid = os.fork()
+            if pid == 0:
+                try:
+                    result = SessionContext().sql("SELECT 2 AS a").to_pydict()
+                    os._exit(0 if result == {"a": [2]} else 1)
+                except BaseException:
+                    os._exit(2)
+
+            # a child blocked on a lock held by a thread of the parent never exits
+            deadline = time.monotonic() + 10
+            while True:
+                done, status = os.waitpid(pid, os.WNOHANG)
+                if done:
+                    break
+                if time.monotonic() > deadline:
+                    os.kill(pid, signal.SIGKILL)
+                    os.waitpid(pid, 0)
+                    pytest.fail("The forked process did not finish its query")
+                time.sleep(0.01)
+            assert os.WIFEXITED(status) and os.WEXITSTATUS(status) == 0
+    finally:
+        stop.set()
+        for thread in threads:
+            thread.join()
+
+
 def test_create_context_with_all_valid_args():
     runtime = RuntimeConfig().with_disk_manager_os().with_fair_spill_pool(10000000)
     config = (
@@ -195,6 +270,113 @@ def test_from_arrow_sources(ctx, source) -> None:
     assert df.count() == 3
 
 
+class ArrowStreamSource:
+    """Exposes only the Arrow PyCapsule stream interface, like non-pyarrow libraries."""
+
+    def __init__(self, num_batches: int) -> None:
+        self.batches_read = 0
+        self.num_batches = num_batches
+
+    def _batches(self):
+        for batch in record_batch_generator(self.num_batches):
+            self.batches_read += 1
+            yield batch
+
+    def __arrow_c_stream__(self, requested_schema=None):
+        schema = pa.schema([("a", pa.int64()), ("b", pa.int64())])
+        reader = pa.RecordBatchReader.from_batches(schema, self._batches())
+        return reader.__arrow_c_stream__(requested_schema)
+
+
+def test_register_arrow_stream(ctx):
+    source = ArrowStreamSource(num_batches=4)
+    ctx.register_arrow_stream("t", source)
+
+    # nothing is read until the table is scanned
+    assert source.batches_read == 0
+
+    result = ctx.sql("SELECT sum(a) AS a, count(*) AS n FROM t").to_pydict()
+    assert result == {"a": [24], "n": [12]}
+    assert source.batches_read == 4
+
+    with pytest.raises(Exception, match="already been consumed"):
+        ctx.sql("SELECT * FROM t").collect()
+
+
+class FailingArrowStreamSource:
+    def __arrow_c_stream__(self, requested_schema=None):
+        raise RuntimeError("source is unavailable")
+
+
+def test_register_arrow_stream_error(ctx):
+    with pytest.raises(RuntimeError, match="source is unavailable"):
+        ctx.register_arrow_stream("t", FailingArrowStreamSource())
+
+    with pytest.raises(TypeError, match="__arrow_c_stream__ or __arrow_c_array__"):
+        ctx.register_arrow_stream("t", object())
+
+
+def test_register_arrow_stream_array(ctx):
+    ctx.register_arrow_stream("t", pa.array([{"a": 1, "b": 4}, {"a": 2, "b": 5}]))
+    assert ctx.sql("SELECT sum(b) AS b FROM t").to_pydict() == {"b": [9]}
+    assert ctx.sql("SELECT sum(b) AS b FROM t").to_pydict() == {"b": [9]}
+
+
+def test_from_arrow_streaming(ctx):
+    source = ArrowStreamSource(num_batches=2)
+    df = ctx.from_arrow(source, name="tbl", streaming=True)
+
+    assert source.batches_read == 0
+    assert df.schema().names == ["a", "b"]
+    assert df.select("b").to_pydict() == {"b": [4, 5, 6, 4, 5, 6]}
+    assert list(ctx.catalog().database().names()) == ["tbl"]
+
+
+def test_register_batch_iterator_generator(ctx):
+    pulled = []
+
+    def batches():
+        for i in range(3):
+            pulled.append(i)
+            yield pa.RecordBatch.from_pydict({"a": [i, i]})
+
+    schema = pa.schema([("a", pa.int64())])
+    ctx.register_batch_iterator("t", batches(), schema)
+    assert pulled == []
+
+    # planning the query does not pull any batches either
+    df = ctx.sql("SELECT a FROM t")
+    assert pulled == []
+
+    assert df.to_pydict() == {"a": [0, 0, 1, 1, 2, 2]}
+    assert pulled == [0, 1, 2]
+
+    with pytest.raises(Exception, match="already been consumed"):
+        ctx.sql("SELECT a FROM t").collect()
+
+
+def test_register_batch_iterator_factory(ctx):
+    schema = pa.schema([("a", pa.int64())])
+
+    def batches():
+        return iter([pa.RecordBatch.from_pydict({"a": [1, 2, 3]}, schema=schema)])
+
+    ctx.register_batch_iterator("t", batches, schema)
+    for _ in range(2):
+        result = ctx.sql("SELECT sum(a) AS a FROM t").to_pydict()
+        assert result == {"a": [6]}
+
+
+def test_register_batch_iterator_error(ctx):
+    def batches():
+        yield pa.RecordBatch.from_pydict({"a": [1]})
+        raise ValueError("source went away")
+
+    ctx.register_batch_iterator("t", batches(), pa.schema([("a", pa.int64())]))
+    with pytest.raises(Exception, match="source went away"):
+        ctx.sql("SELECT * FROM t").collect()
+
+
 def test_from_arrow_table_with_name(ctx):
     # create a PyArrow table
     data = {"a": [1, 2, 3], "b": [4, 5, 6]}
@@ -542,6 +724,78 @@ def test_read_json_compressed(ctx, tmp_path):
     assert result[0].column(1) == pa.array([1, 2, 3])
 
 
+def test_read_json_array(ctx, tmp_path):
+    path = tmp_path / "data.json"
+    path.write_text('[{"A": "a", "B": 1}, {"A": "b", "B": 2}, {"A": "c", "B": null}]')
+
+    df = ctx.read_json(path, json_format="array")
+
+    assert df.to_pydict() == {"A": ["a", "b", "c"], "B": [1, 2, None]}
+
+    ctx.register_json("array", path, json_format="array")
+    result = ctx.sql("SELECT sum(B) AS total FROM array").to_pydict()
+    assert result == {"total": [3]}
+
+    gzip_path = tmp_path / "data.json.gz"
+    with gzip.open(gzip_path, "wt") as f:
+        f.write(path.read_text())
+    df = ctx.read_json(
+        gzip_path,
+        file_extension=".gz",
+        file_compression_type="gzip",
+        json_format="array",
+    )
+    assert df.count() == 3
+
+
+def test_read_json_schema_hints(ctx, tmp_path):
+    path = tmp_path / "events.json"
+    path.write_text(
+        '{"id": 1, "event": {"ts": "2024-01-02T03:04:05", "tags": ["a"]}}\n'
+        '{"id": 2, "event": null}\n'
+    )
+    event_type = pa.struct(
+        [("ts", pa.timestamp("us")), ("tags", pa.list_(pa.string()))]
+    )
+
+    df = ctx.read_json(path, schema_hints={"event": event_type, "extra": pa.string()})
+
+    schema = df.schema()
+    assert schema.field("id").type == pa.int64()
+    assert schema.field("event").type == event_type
+    assert schema.field("extra").type == pa.string()
+    assert df.to_pydict()["event"] == [
+        {"ts": dt.datetime(2024, 1, 2, 3, 4, 5), "tags": ["a"]},
+        None,
+    ]
+
+    hints = pa.schema([("event", event_type)])
+    df = ctx.read_json(path, schema_hints=hints, json_format="ndjson")
+    assert df.schema().field("event").type == event_type
+
+    schema = pa.schema([("id", pa.int64()), ("event", event_type)])
+    with pytest.raises(ValueError, match="schema and schema_hints cannot both"):
+        ctx.read_json(path, schema=schema, schema_hints=hints)
+    with pytest.raises(ValueError, match="schema and schema_hints cannot both"):
+        ctx.register_json("events", path, schema=schema, schema_hints=hints)
+
+
+def test_read_json_invalid_format(ctx, tmp_path):
+    path = tmp_path / "data.json"
+    path.write_text('{"A": "a"}\n')
+
+    with pytest.raises(ValueError, match="json_format must be one of"):
+        ctx.read_json(path, json_format="xml")
+
+    with pytest.raises(ValueError, match="not supported for JSON array files"):
+        ctx.read_json(
+            path, table_partition_cols=[("p", "string")], json_format="array"
+        )
+
+    with pytest.raises(Exception, match="is not a JSON array of objects"):
+        ctx.read_json(path, json_format="array")
+
+
 def test_read_csv(ctx):
     csv_df = ctx.read_csv(path="testing/data/csv/aggregate_test_100.csv")
     csv_df.select(column("c1")).show()
@@ -577,6 +831,76 @@ def test_read_csv_compressed(ctx, tmp_path):
     csv_df.select(column("c1")).show()
 
 
+def test_read_csv_options(ctx, tmp_path):
+    path = tmp_path / "vendor.csv"
+    path.write_text(
+        "# exported by vendor\n"
+        "id;note\n"
+        "1;'it\\'s'\n"
+        "2;'multi\nline'\n"
+    )
+
+    df = ctx.read_csv(
+        path,
+        delimiter=";",
+        quote="'",
+        escape="\\",
+        comment="#",
+        newlines_in_values=True,
+    )
+
+    assert df.to_pydict() == {"id": [1, 2], "note": ["it's", "multi\nline"]}
+
+    with pytest.raises(ValueError, match="Quote must be a single character"):
+        ctx.read_csv(path, quote="''")
+
+
+def test_read_csv_null_regex(ctx, tmp_path):
+    path = tmp_path / "nulls.csv"
+    path.write_text("a,b,c\n1,NA,x\n-,2.5,NA\n3,,y\n")
+
+    df = ctx.read_csv(path, null_regex="^(NA|-)$")
+
+    schema = df.schema()
+    assert schema.field("a").type == pa.int64()
+    assert schema.field("b").type == pa.float64()
+    assert df.to_pydict() == {
+        "a": [1, None, 3],
+        "b": [None, 2.5, None],
+        "c": ["x", None, "y"],
+    }
+
+    schema = pa.schema([("a", pa.int32()), ("b", pa.float32()), ("c", pa.string())])
+    df = ctx.read_csv(path, schema=schema, null_regex="^(NA|-)$")
+    assert df.schema() == schema
+    assert df.to_pydict()["a"] == [1, None, 3]
+
+
+def test_read_csv_null_regex_matches_whole_values(ctx, tmp_path):
+    path = tmp_path / "nulls.csv"
+    path.write_text("a,b\n1,NA\n2,NAN\n3,xNA\n")
+
+    df = ctx.read_csv(path, null_regex="NA")
+
+    assert df.schema().field("b").type == pa.string()
+    assert df.to_pydict()["b"] == [None, "NAN", "xNA"]
+
+
+def test_read_csv_null_regex_parses_like_csv(ctx, tmp_path):
+    path = tmp_path / "decimals.csv"
+    path.write_text("a\n1.005\nNA\n")
+    schema = pa.schema([("a", pa.decimal128(5, 2))])
+
+    # the CSV reader truncates the extra digits where a cast would round them
+    df = ctx.read_csv(path, schema=schema, null_regex="NA")
+    assert df.to_pydict()["a"] == [Decimal("1.00"), None]
+
+
+def test_read_csv_null_regex_no_files(ctx, tmp_path):
+    with pytest.raises(Exception, match="No files with the extension '.csv'"):
+        ctx.read_csv(tmp_path, null_regex="NA")
+
+
 def test_read_parquet(ctx):
     parquet_df = ctx.read_parquet(path="parquet/data/alltypes_plain.parquet")
     parquet_df.show()
@@ -587,6 +911,35 @@ def test_read_parquet(ctx):
     assert parquet_df is not None
 
 
+def test_read_parquet_options(ctx, tmp_path):
+    path = tmp_path / "data.parquet"
+    table = pa.table(
+        {
+            "id": list(range(100)),
+            "payload": pa.array([b"value %d" % i for i in range(100)], pa.binary()),
+        }
+    )
+    pq.write_table(table, path, row_group_size=10, write_page_index=True)
+
+    df = ctx.read_parquet(
+        path,
+        enable_page_index=True,
+        bloom_filter_on_read=False,
+        pushdown_filters=True,
+        reorder_filters=True,
+        metadata_size_hint=64 * 1024,
+    )
+    result = df.filter(column("id") >= literal(95)).select(column("id")).to_pydict()
+    assert result == {"id": [95, 96, 97, 98, 99]}
+
+    df = ctx.read_parquet(path, binary_as_string=True, schema_force_view_types=False)
+    assert df.schema().field("payload").type == pa.string()
+    assert df.to_pydict()["payload"][0] == "value 0"
+
+    df = ctx.read_parquet(path, schema_force_view_types=False)
+    assert df.schema().field("payload").type == pa.binary()
+
+
 def test_read_avro(ctx):
     avro_df = ctx.read_avro(path="testing/data/avro/alltypes_plain.avro")
     avro_df.show()
@@ -624,6 +977,23 @@ def test_sql_with_options_no_dml(ctx):
         ctx.sql_with_options(sql, options=options)
 
 
+def test_sql_async(ctx):
+    async def run():
+        await ctx.sql_async("CREATE TABLE t AS VALUES (1, 'a'), (2, 'b')")
+        df = await ctx.sql_async("SELECT count(*) AS n FROM t")
+        return await df.collect_async()
+
+    batches = asyncio.run(run())
+    assert batches[0].column(0) == pa.array([2], type=pa.int64())
+
+
+def test_sql_async_with_options(ctx):
+    sql = "CREATE TABLE IF NOT EXISTS valuetable AS VALUES(1,'HELLO')"
+    options = SQLOptions().with_allow_ddl(False)
+    with pytest.raises(Exception, match="DDL"):
+        asyncio.run(ctx.sql_async(sql, options=options))
+
+
 def test_sql_with_options_no_statements(ctx):
     sql = "SET time zone = 1;"
     ctx.sql(sql)
@@ -14,17 +14,28 @@
 # KIND, either express or implied.  See the License for the
 # specific language governing permissions and limitations
 # under the License.
+import asyncio
+import datetime
+import gzip
 import os
+import threading
+import time
 from typing import Any
 
 import pyarrow as pa
 from pyarrow.csv import write_csv
+import pyarrow.ipc as ipc
 import pyarrow.parquet as pq
 import pytest
 
 from datafusion import functions as f
 from datafusion import (
+    CancellationToken,
     DataFrame,
+    ParquetColumnOptions,
+    ParquetWriterOptions,
+    QueryCancelledError,
+    SessionConfig,
     SessionContext,
     WindowFrame,
     column,
@@ -977,6 +988,111 @@ def test_execute_stream_partitioned(df):
     )  # after one iteration all generators must be exhausted
 
 
+def test_collect_async(df):
+    batches = asyncio.run(df.collect_async())
+    assert batches == df.collect()
+
+
+def test_execute_stream_async(df):
+    async def consume():
+        stream = await df.execute_stream_async()
+        return [batch.to_pyarrow() async for batch in stream]
+
+    batches = asyncio.run(consume())
+    table = pa.Table.from_batches(batches)
+    assert table.shape == (3, 3)
+    assert table.to_pydict() == df.to_pydict()
+
+
+class SlowSource:
+    """Batches produced every 50ms, bounded so that a query that is not
+    cancelled ends after a few seconds instead of hanging"""
+
+    def __init__(self, batches=100):
+        self.batches = batches
+        self.pulled = 0
+
+    def __call__(self):
+        for i in range(self.batches):
+            time.sleep(0.05)
+            self.pulled += 1
+            yield pa.RecordBatch.from_pydict({"a": [i]})
+
+
+def run_with_timeout(func, timeout=10):
+    """Call ``func`` in another thread, failing instead of hanging when it does
+    not return within ``timeout`` seconds"""
+    result = {}
+
+    def target():
+        try:
+            result["value"] = func()
+        except BaseException as e:
+            result["error"] = e
+
+    thread = threading.Thread(target=target, daemon=True)
+    thread.start()
+    thread.join(timeout)
+    assert not thread.is_alive(), f"Call did not return within {timeout} seconds"
+    if "error" in result:
+        raise result["error"]
+    return result["value"]
+
+
+@pytest.fixture
+def slow_source():
+    return SlowSource()
+
+
+@pytest.fixture
+def slow_df(slow_source):
+    ctx = SessionContext(SessionConfig().with_target_partitions(2))
+    ctx.register_batch_iterator("slow", slow_source, pa.schema([("a", pa.int64())]))
+    return ctx.sql("SELECT sum(a) FROM slow")
+
+
+def test_collect_timeout(slow_df):
+    with pytest.raises(QueryCancelledError, match="timeout"):
+        run_with_timeout(lambda: slow_df.collect(timeout=0.1))
+
+
+def test_collect_timeout_stops_reading_source(slow_df, slow_source):
+    with pytest.raises(QueryCancelledError, match="timeout"):
+        run_with_timeout(lambda: slow_df.collect(timeout=0.1))
+
+    pulled = slow_source.pulled
+    time.sleep(0.5)
+    # a batch that was being produced when the query was cancelled may complete
+    assert slow_source.pulled <= pulled + 1
+    assert slow_source.pulled < slow_source.batches
+
+
+def test_collect_with_cancellation_token(slow_df):
+    token = CancellationToken()
+    assert not token.cancelled
+
+    timer = threading.Timer(0.1, token.cancel)
+    timer.start()
+    with pytest.raises(QueryCancelledError, match="cancelled"):
+        run_with_timeout(lambda: slow_df.collect(token=token))
+    assert token.cancelled
+
+
+def test_execute_stream_timeout(slow_df):
+    stream = slow_df.execute_stream(timeout=0.1)
+    with pytest.raises(QueryCancelledError):
+        run_with_timeout(stream.next)
+
+
+def test_collect_invalid_timeout(df):
+    with pytest.raises(ValueError, match="Invalid timeout"):
+        df.collect(timeout=-1)
+
+
+def test_collect_timeout_not_reached(df):
+    assert df.collect(timeout=60) == df.collect()
+
+
 def test_empty_to_arrow_table(df):
     # Convert empty datafusion dataframe to pyarrow Table
     pyarrow_table = df.limit(0).to_arrow_table()
@@ -1039,6 +1155,38 @@ def test_write_csv(ctx, df, tmp_path, path_to_str):
     assert result == expected
 
 
+def test_write_csv_options(ctx, tmp_path):
+    df = ctx.from_pydict({"a": [1, 2], "b": [datetime.date(2024, 1, 2), None]})
+    path = tmp_path / "out.tsv"
+
+    df.write_csv(
+        path,
+        with_header=True,
+        delimiter="\t",
+        null_value="NA",
+        date_format="%d/%m/%Y",
+    )
+
+    assert path.read_text().splitlines() == ["a\tb", "1\t02/01/2024", "2\tNA"]
+
+
+def test_write_csv_compressed(df, tmp_path):
+    path = tmp_path / "out.csv.gz"
+
+    df.write_csv(path, with_header=True, file_compression_type="gzip")
+
+    with gzip.open(path, "rt") as f:
+        assert f.read().splitlines() == ["a,b,c", "1,4,8", "2,5,5", "3,6,8"]
+
+
+def test_write_csv_invalid_options(df, tmp_path):
+    with pytest.raises(ValueError, match="Delimiter must be a single character"):
+        df.write_csv(tmp_path, delimiter="||")
+
+    with pytest.raises(ValueError, match="file_compression_type"):
+        df.write_csv(tmp_path, file_compression_type="rar")
+
+
 @pytest.mark.parametrize("path_to_str", (True, False))
 def test_write_json(ctx, df, tmp_path, path_to_str):
     path = str(tmp_path) if path_to_str else tmp_path
@@ -1052,6 +1200,78 @@ def test_write_json(ctx, df, tmp_path, path_to_str):
     assert result == expected
 
 
+def test_write_json_options(ctx, tmp_path):
+    df = ctx.from_pydict(
+        {
+            "a": [1, 2],
+            "ts": [datetime.datetime(2024, 1, 2, 3, 4, 5), None],
+            "d": [datetime.date(2024, 1, 2), None],
+        }
+    )
+    path = tmp_path / "out.json.gz"
+
+    df.write_json(
+        path,
+        file_compression_type="gzip",
+        date_format="%d/%m/%Y",
+        timestamp_format="%Y-%m-%d %H:%M",
+    )
+
+    with gzip.open(path, "rt") as f:
+        assert f.read().splitlines() == [
+            '{"a":1,"ts":"2024-01-02 03:04","d":"02/01/2024"}',
+            '{"a":2}',
+        ]
+
+    with pytest.raises(ValueError, match="file_compression_type"):
+        df.write_json(tmp_path, file_compression_type="rar")
+
+
+def test_write_table(ctx, tmp_path):
+    df = ctx.from_pydict({"a": [1, 2, 3], "b": [4, 5, 6], "c": [8, 5, 8]})
+    ctx.register_listing_table(
+        "listing", tmp_path, file_extension=".parquet", schema=df.schema()
+    )
+
+    df.write_table("listing")
+    df.write_table("listing")
+
+    result = ctx.table("listing").aggregate([], [f.count(column("a"))]).to_pydict()
+    assert list(result.values()) == [[6]]
+
+    empty = pa.RecordBatch.from_pylist([], schema=df.schema())
+    ctx.register_record_batches("memory", [[empty]])
+    df.write_table("memory")
+    assert ctx.table("memory").sort(column("a")).to_pydict() == df.to_pydict()
+
+
+def test_write_table_overwrite(ctx, tmp_path):
+    df = ctx.from_pydict({"a": [1, 2, 3], "b": [4, 5, 6], "c": [8, 5, 8]})
+    ctx.register_listing_table(
+        "listing", tmp_path, file_extension=".parquet", schema=df.schema()
+    )
+    df.write_table("listing")
+    df.write_table("listing")
+
+    # the overwritten table can be read by the written DataFrame
+    doubled = ctx.table("listing").select(
+        (column("a") * literal(2)).alias("a"), column("b"), column("c")
+    )
+    doubled = doubled.distinct()
+    doubled.write_table("listing", overwrite=True)
+
+    result = ctx.table("listing").sort(column("a")).to_pydict()
+    assert result == {"a": [2, 4, 6], "b": [4, 5, 6], "c": [8, 5, 8]}
+
+    empty = pa.RecordBatch.from_pylist([], schema=df.schema())
+    ctx.register_record_batches("memory", [[empty]])
+    with pytest.raises(Exception, match="not implemented for MemoryTable"):
+        df.write_table("memory", overwrite=True)
+
+    with pytest.raises(Exception, match="Table 'missing' not found"):
+        df.write_table("missing", overwrite=True)
+
+
 @pytest.mark.parametrize("path_to_str", (True, False))
 def test_write_parquet(df, tmp_path, path_to_str):
     path = str(tmp_path) if path_to_str else tmp_path
@@ -1089,6 +1309,160 @@ def test_write_compressed_parquet(df, tmp_path, compression, compression_level):
     assert result == expected
 
 
+def test_write_parquet_partition_by(df, tmp_path):
+    path = tmp_path / "table"
+    df.write_parquet(path, partition_by=["c"])
+
+    assert sorted(os.listdir(path)) == ["c=5", "c=8"]
+    result = pq.read_table(path / "c=8").sort_by("a").to_pydict()
+    assert result == {"a": [1, 3], "b": [4, 6]}
+
+
+@pytest.mark.parametrize("write", ["csv", "json", "parquet"])
+def test_write_single_file_output(df, tmp_path, write):
+    write_fn = getattr(df, f"write_{write}")
+
+    # a path with a file extension is written as a directory on request
+    path = tmp_path / f"data.{write}"
+    write_fn(path, single_file_output=False)
+    assert path.is_dir()
+
+    path = tmp_path / f"single.{write}"
+    write_fn(path, single_file_output=True)
+    assert path.is_file()
+
+    with pytest.raises(ValueError, match="file extension"):
+        write_fn(tmp_path / "no_extension", single_file_output=True)
+
+    with pytest.raises(ValueError, match="partition_by"):
+        write_fn(path, single_file_output=True, partition_by=["c"])
+
+
+def test_write_overwrite(df, tmp_path):
+    path = tmp_path / "table"
+
+    df.write_parquet(path)
+    df.write_parquet(path)
+    assert pq.read_table(path).num_rows == 6
+
+    df.write_parquet(path, overwrite=True)
+    assert pq.read_table(path).num_rows == 3
+
+
+def test_write_overwrite_from_same_path(df, tmp_path):
+    path = tmp_path / "table"
+    df.write_parquet(path)
+    df.write_parquet(path)
+
+    ctx = SessionContext()
+    ctx.read_parquet(path).write_parquet(path, overwrite=True)
+    assert pq.read_table(path).num_rows == 6
+
+    file_path = tmp_path / "data.parquet"
+    df.write_parquet(file_path, single_file_output=True)
+    ctx.read_parquet(file_path).union(df).write_parquet(
+        file_path, single_file_output=True, overwrite=True
+    )
+    assert pq.read_table(file_path).num_rows == 6
+
+
+def test_write_overwrite_keeps_foreign_files(df, tmp_path):
+    path = tmp_path / "table"
+    df.write_parquet(path, partition_by=["b"])
+    df.write_parquet(path)
+    (path / "notes.txt").write_text("keep")
+    (path / "nested").mkdir()
+    (path / "nested" / "data.parquet").write_text("keep")
+
+    df.write_parquet(path, overwrite=True)
+
+    assert (path / "notes.txt").read_text() == "keep"
+    assert (path / "nested" / "data.parquet").read_text() == "keep"
+    # the Hive partitions written before are replaced
+    partitions = [p for p in path.rglob("*.parquet") if p.parent.name.startswith("b=")]
+    assert partitions == []
+    assert list(path.glob("*.parquet"))
+
+
+def test_write_overwrite_failure_keeps_files(df, tmp_path):
+    path = tmp_path / "table"
+    df.write_parquet(path)
+
+    ctx = SessionContext()
+    failing = ctx.sql("SELECT CAST('not a number' AS INT) AS a")
+    with pytest.raises(Exception, match="not a number"):
+        failing.write_parquet(path, overwrite=True)
+    assert pq.read_table(path).num_rows == 3
+
+
+def test_write_sort_by(df, tmp_path):
+    path = tmp_path / "sorted.parquet"
+    df.write_parquet(path, sort_by=[column("a").sort(ascending=False)])
+
+    assert pq.read_table(path).to_pydict()["a"] == [3, 2, 1]
+
+
+@pytest.mark.parametrize("compression", [None, "lz4", "zstd"])
+def test_write_arrow(df, tmp_path, compression):
+    path = tmp_path / "data.arrow"
+    df.write_arrow(path, compression=compression)
+
+    with ipc.open_file(path) as reader:
+        assert reader.read_all().to_pydict() == df.to_pydict()
+
+    ctx = SessionContext()
+    assert ctx.read_arrow(path).to_pydict() == df.to_pydict()
+
+
+def test_write_arrow_stream(df, tmp_path):
+    path = tmp_path / "data.arrows"
+    df.write_arrow(path, format="stream", sort_by=[column("a").sort(ascending=False)])
+
+    with ipc.open_stream(path) as reader:
+        assert reader.read_all().to_pydict()["a"] == [3, 2, 1]
+
+
+def test_write_arrow_invalid_options(df, tmp_path):
+    with pytest.raises(ValueError, match="format"):
+        df.write_arrow(tmp_path / "data.arrow", format="feather")
+    with pytest.raises(ValueError, match="compression"):
+        df.write_arrow(tmp_path / "data.arrow", compression="gzip")
+
+
+def test_write_parquet_with_options(df, tmp_path):
+    path = tmp_path / "data.parquet"
+
+    options = ParquetWriterOptions(
+        compression="gzip(6)",
+        max_row_group_size=1,
+        statistics_enabled="page",
+        key_value_metadata={"owner": "tests"},
+        column_specific_options={
+            "a": ParquetColumnOptions(compression="snappy", bloom_filter_enabled=True),
+        },
+    )
+    df.write_parquet_with_options(path, options)
+
+    parquet_file = pq.ParquetFile(path)
+    metadata = parquet_file.metadata
+    assert metadata.num_row_groups == 3
+    assert metadata.metadata[b"owner"] == b"tests"
+    for i in range(metadata.num_row_groups):
+        row_group = metadata.row_group(i)
+        assert row_group.column(0).compression == "SNAPPY"
+        assert row_group.column(1).compression == "GZIP"
+
+    assert parquet_file.read().to_pydict() == df.to_pydict()
+
+
+def test_write_parquet_with_invalid_options(df, tmp_path):
+    options = ParquetWriterOptions(
+        column_specific_options={"a": ParquetColumnOptions(encoding="not_an_encoding")}
+    )
+    with pytest.raises(Exception, match="not_an_encoding"):
+        df.write_parquet_with_options(tmp_path / "data.parquet", options)
+
+
 @pytest.mark.parametrize(
     "compression, compression_level",
     [("gzip", 12), ("brotli", 15), ("zstd", 23), ("wrong", 12)],
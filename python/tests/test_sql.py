@@ -21,10 +21,11 @@ import numpy as np
 import pyarrow as pa
 from pyarrow.csv import write_csv
 import pyarrow.dataset as ds
+import pyarrow.feather as feather
 import pytest
 from datafusion.object_store import Http
 
-from datafusion import udf, col
+from datafusion import SessionConfig, SessionContext, udf, col
 
 from . import generic as helpers
 
@@ -139,6 +140,16 @@ def test_register_csv_list(ctx, tmp_path):
     assert int_sum == 2 * sum(int_values)
 
 
+def test_register_csv_null_regex(ctx, tmp_path):
+    path = tmp_path / "nulls.csv"
+    path.write_text("int|str\n1|a\nN/A|b\n3|N/A\n")
+
+    ctx.register_csv("csv", [path, path], delimiter="|", null_regex="^N/A$")
+
+    result = ctx.sql("SELECT sum(int) AS total, count(str) AS cnt FROM csv")
+    assert result.to_pydict() == {"total": [8], "cnt": [4]}
+
+
 def test_register_http_csv(ctx):
     url = "https://raw.githubusercontent.com/ibis-project/testing-data/refs/heads/master/csv/diamonds.csv"
     ctx.register_object_store("", Http(url))
@@ -159,6 +170,26 @@ def test_register_parquet(ctx, tmp_path):
     assert result.to_pydict() == {"cnt": [100]}
 
 
+def test_register_parquet_options(tmp_path):
+    ctx = SessionContext(SessionConfig().with_information_schema(True))
+    path = helpers.write_parquet(tmp_path / "a.parquet", helpers.data())
+    ctx.register_parquet(
+        "t",
+        path,
+        pushdown_filters=True,
+        reorder_filters=True,
+        enable_page_index=False,
+        metadata_size_hint=1024,
+    )
+
+    result = ctx.sql("SELECT COUNT(a) AS cnt FROM t WHERE a IS NOT NULL").to_pydict()
+    assert result == {"cnt": [100]}
+
+    # the options only apply to the registered table
+    result = ctx.sql("SHOW datafusion.execution.parquet.pushdown_filters").to_pydict()
+    assert result["value"] == ["false"]
+
+
 @pytest.mark.parametrize("path_to_str", (True, False))
 def test_register_parquet_partitioned(ctx, tmp_path, path_to_str):
     dir_root = tmp_path / "dataset_parquet_partitioned"
@@ -272,6 +303,19 @@ def test_register_json(ctx, tmp_path):
         ctx.register_json("json4", gzip_path, file_compression_type="rar")
 
 
+def test_register_arrow(ctx, tmp_path):
+    table = pa.table({"a": [1, 2, 3], "b": ["x", "y", "z"]})
+    path = tmp_path / "data.arrow"
+    feather.write_feather(table, path)
+
+    ctx.register_arrow("t", path)
+    result = ctx.sql("SELECT SUM(a) AS total FROM t").collect()
+    assert result[0].column(0) == pa.array([6])
+
+    ctx.register_arrow("t2", tmp_path, schema=table.schema)
+    assert ctx.table("t2").to_arrow_table() == table
+
+
 def test_register_avro(ctx):
     path = "testing/data/avro/alltypes_plain.avro"
     ctx.register_avro("alltypes_plain", path)
@@ -535,3 +579,66 @@ def test_register_listing_table(
 
     rd = result.to_pydict()
     assert dict(zip(rd["grp"], rd["count"])) == {"a": 3, "b": 2}
+
+
+def test_register_listing_table_file_list(ctx, tmp_path):
+    (tmp_path / "2024-01-01").mkdir()
+    (tmp_path / "2024-01-02").mkdir()
+    table = pa.Table.from_arrays(
+        [[1, 2, 3, 4], ["a", "b", "c", "d"]], names=["int", "str"]
+    )
+    pa.parquet.write_table(table.slice(0, 2), tmp_path / "2024-01-01/part-0.parquet")
+    pa.parquet.write_table(table.slice(2, 2), tmp_path / "2024-01-02/part-0.parquet")
+    # not part of the snapshot
+    pa.parquet.write_table(table, tmp_path / "2024-01-02/part-1.parquet")
+    files = [
+        tmp_path / "2024-01-01/part-0.parquet",
+        tmp_path / "2024-01-02/part-0.parquet",
+    ]
+
+    ctx.register_listing_table(
+        "snapshot",
+        files,
+        schema=table.schema,
+        file_sort_order=[[col("int").sort()]],
+    )
+    result = ctx.sql("SELECT sum(int) AS total FROM snapshot").to_pydict()
+    assert result == {"total": [10]}
+
+    ctx.register_listing_table("inferred", [str(f) for f in files])
+    assert ctx.table("inferred").count() == 4
+
+
+def test_register_listing_table_file_list_with_directory(ctx, tmp_path):
+    table = pa.Table.from_arrays([[1, 2, 3]], names=["int"])
+    (tmp_path / "dir").mkdir()
+    pa.parquet.write_table(table, tmp_path / "dir/part-0.parquet")
+    # only the parquet files of a directory are read
+    (tmp_path / "dir/_SUCCESS").write_text("")
+    pa.parquet.write_table(table, tmp_path / "other.parquet")
+    pa.parquet.write_table(table, tmp_path / "other")
+
+    ctx.register_listing_table("dir", [tmp_path / "dir"])
+    assert ctx.table("dir").count() == 3
+
+    ctx.register_listing_table("mixed", [tmp_path / "dir", tmp_path / "other.parquet"])
+    assert ctx.table("mixed").count() == 6
+
+    with pytest.raises(ValueError, match="does not match the extension '.parquet'"):
+        ctx.register_listing_table("mismatch", [tmp_path / "dir", tmp_path / "other"])
+
+
+def test_register_listing_table_file_format(ctx, tmp_path):
+    path = tmp_path / "data.txt"
+    path.write_text("int;str\n1;a\n2;b\n")
+
+    ctx.register_listing_table(
+        "csv",
+        [path],
+        file_format="csv",
+        format_options={"has_header": True, "delimiter": ";"},
+    )
+    assert ctx.table("csv").to_pydict() == {"int": [1, 2], "str": ["a", "b"]}
+
+    with pytest.raises(ValueError, match="Unsupported file format 'xml'"):
+        ctx.register_listing_table("xml", tmp_path, file_format="xml")
@@ -15,13 +15,23 @@
 # specific language governing permissions and limitations
 # under the License.
 
-from typing import List
+from typing import List, Optional
 
 import pyarrow as pa
 import pyarrow.compute as pc
 import pytest
 
-from datafusion import Accumulator, column, udaf
+from datafusion import (
+    Accumulator,
+    AggregateUDF,
+    WindowFrame,
+    GroupsAccumulator,
+    RuntimeConfig,
+    SessionContext,
+    column,
+    udaf,
+)
+from datafusion.expr import Window
 
 
 class Summarize(Accumulator):
@@ -47,6 +57,143 @@ class Summarize(Accumulator):
         return self._sum
 
 
+class Mean(Accumulator):
+    """Keeps the sum and count in a single struct state."""
+
+    state_type = pa.struct([("sum", pa.float64()), ("count", pa.int64())])
+
+    def __init__(self):
+        self._sum = 0.0
+        self._count = 0
+
+    def state(self) -> List[pa.Scalar]:
+        return [pa.scalar({"sum": self._sum, "count": self._count}, self.state_type)]
+
+    def update(self, values: pa.Array) -> None:
+        self._sum += pc.sum(values).as_py() or 0.0
+        self._count += pc.count(values).as_py()
+
+    def merge(self, states: List[pa.Array]) -> None:
+        for state in states[0].to_pylist():
+            self._sum += state["sum"]
+            self._count += state["count"]
+
+    def evaluate(self) -> pa.Scalar:
+        return pa.scalar(self._sum / self._count if self._count else None)
+
+
+class Median(Accumulator):
+    """Collects every value into a list state."""
+
+    def __init__(self):
+        self._values: List[float] = []
+
+    def state(self) -> List[List[float]]:
+        # plain python values are converted to the declared state type
+        return [self._values]
+
+    def update(self, values: pa.Array) -> None:
+        self._values.extend(v for v in values.to_pylist() if v is not None)
+
+    def merge(self, states: List[pa.Array]) -> None:
+        for values in states[0].to_pylist():
+            self._values.extend(values)
+
+    def evaluate(self) -> pa.Scalar:
+        values = sorted(self._values)
+        mid = len(values) // 2
+        if len(values) % 2:
+            return pa.scalar(values[mid])
+        return pa.scalar((values[mid - 1] + values[mid]) / 2)
+
+
+class FirstBy(Accumulator):
+    """Returns the value of the row with the smallest ordering key."""
+
+    def __init__(self):
+        self._value = None
+        self._key = None
+
+    def state(self) -> List[pa.Scalar]:
+        return [pa.scalar(self._value, pa.float64()), pa.scalar(self._key, pa.int64())]
+
+    def _add(self, value, key) -> None:
+        if key is not None and (self._key is None or key < self._key):
+            self._value, self._key = value, key
+
+    def update(self, values: pa.Array, keys: pa.Array) -> None:
+        # the input is sorted by the ordering key, so only the first row matters
+        if len(values) > 0:
+            self._add(values[0].as_py(), keys[0].as_py())
+
+    def merge(self, states: List[pa.Array]) -> None:
+        for value, key in zip(states[0].to_pylist(), states[1].to_pylist()):
+            self._add(value, key)
+
+    def evaluate(self) -> pa.Scalar:
+        return pa.scalar(self._value, pa.float64())
+
+
+class RetractableSummarize(Summarize):
+    def supports_retract_batch(self) -> bool:
+        return True
+
+    def retract_batch(self, values: pa.Array) -> None:
+        self._sum = pa.scalar(self._sum.as_py() - pc.sum(values).as_py())
+
+
+class LargeSummarize(Summarize):
+    """Reports a state much larger than the memory pool allows."""
+
+    def size(self) -> int:
+        return 1 << 40
+
+
+class FailingSizeSummarize(Summarize):
+    """Raises while reporting its size."""
+
+    def size(self) -> int:
+        raise ValueError("size is unknown")
+
+
+class GroupedSummarize(GroupsAccumulator):
+    """Sums the values of every group in a single python call."""
+
+    def __init__(self):
+        self._sums: List[float] = []
+
+    def _accumulate(self, values, group_indices, opt_filter, total_num_groups):
+        self._sums.extend([0.0] * (total_num_groups - len(self._sums)))
+        filters = (
+            opt_filter.to_pylist()
+            if opt_filter is not None
+            else [True] * len(group_indices)
+        )
+        for value, group, keep in zip(
+            values[0].to_pylist(), group_indices.to_pylist(), filters
+        ):
+            if keep and value is not None:
+                self._sums[group] += value
+
+    def update_batch(self, values, group_indices, opt_filter, total_num_groups):
+        self._accumulate(values, group_indices, opt_filter, total_num_groups)
+
+    def merge_batch(self, values, group_indices, opt_filter, total_num_groups):
+        self._accumulate(values, group_indices, opt_filter, total_num_groups)
+
+    def _emit(self, emit_to: Optional[int]) -> pa.Array:
+        if emit_to is None:
+            emit_to = len(self._sums)
+        emitted, self._sums = self._sums[:emit_to], self._sums[emit_to:]
+        return pa.array(emitted, pa.float64())
+
+    def evaluate(self, emit_to: Optional[int]) -> pa.Array:
+        return self._emit(emit_to)
+
+    def state(self, emit_to: Optional[int]) -> List[pa.Array]:
+        return [self._emit(emit_to)]
+
+
 class NotSubclassOfAccumulator:
     pass
 
@@ -176,3 +323,237 @@ def test_register_udaf(ctx, df) -> None:
     df_result = ctx.sql("select summarize(b) from test_table")
 
     assert df_result.collect()[0][0][0].as_py() == 14.0
+
+
+def test_group_by_groups_accumulator(df):
+    created = []
+
+    def groups_accumulator() -> GroupedSummarize:
+        accum = GroupedSummarize()
+        created.append(accum)
+        return accum
+
+    summarize = udaf(
+        Summarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+        groups_accumulator=groups_accumulator,
+    )
+
+    # Creating the UDAF validates the factory once
+    created.clear()
+
+    result = (
+        df.aggregate([column("b")], [summarize(column("a")).alias("total")])
+        .sort(column("b").sort())
+        .to_pydict()
+    )
+
+    assert result == {"b": [4, 6], "total": [1.0 + 2.0, 3.0]}
+    assert len(created) > 0
+
+    # Aggregates without grouping still use the regular accumulator
+    created.clear()
+    result = df.aggregate([], [summarize(column("a"))]).collect()[0]
+    assert result.column(0) == pa.array([1.0 + 2.0 + 3.0])
+    assert created == []
+
+
+def test_groups_accumulator_errors():
+    with pytest.raises(TypeError):
+        udaf(
+            Summarize,
+            pa.float64(),
+            pa.float64(),
+            [pa.float64()],
+            volatility="immutable",
+            groups_accumulator=Summarize,
+        )
+
+
+def test_accumulator_size_limits_memory():
+    runtime = RuntimeConfig().with_greedy_memory_pool(10 * 1024 * 1024)
+    ctx = SessionContext(runtime=runtime)
+    df = ctx.from_pydict({"a": [1.0, 2.0, 3.0]})
+
+    summarize = udaf(
+        Summarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+    )
+    large = udaf(
+        LargeSummarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+    )
+
+    # The default size is estimated from the state types and fits in the pool
+    result = df.aggregate([], [summarize(column("a"))]).collect()[0]
+    assert result.column(0) == pa.array([6.0])
+    assert Summarize().size() is None
+
+    with pytest.raises(Exception, match="Resources exhausted"):
+        df.aggregate([], [large(column("a"))]).collect()
+
+
+def test_accumulator_size_errors(df):
+    failing = udaf(
+        FailingSizeSummarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+    )
+
+    with pytest.raises(Exception, match="size is unknown"):
+        df.aggregate([], [failing(column("a"))]).collect()
+
+
+def test_udaf_struct_state(df):
+    mean = udaf(
+        Mean,
+        pa.float64(),
+        pa.float64(),
+        [Mean.state_type],
+        volatility="immutable",
+    )
+
+    result = df.aggregate([], [mean(column("a"))]).collect()[0]
+    assert result.column(0) == pa.array([2.0])
+
+    result = (
+        df.aggregate([column("b")], [mean(column("a")).alias("mean")])
+        .sort(column("b").sort())
+        .to_pydict()
+    )
+    assert result == {"b": [4, 6], "mean": [1.5, 3.0]}
+
+
+def test_udaf_list_state(ctx):
+    median = udaf(
+        Median,
+        pa.float64(),
+        pa.float64(),
+        [pa.list_(pa.float64())],
+        volatility="immutable",
+    )
+
+    batches = [
+        [pa.RecordBatch.from_pydict({"a": [1.0, 5.0], "b": ["x", "y"]})],
+        [pa.RecordBatch.from_pydict({"a": [2.0, 7.0, 3.0], "b": ["x", "y", "y"]})],
+    ]
+    df = ctx.create_dataframe(batches)
+
+    result = df.aggregate([], [median(column("a"))]).collect()[0]
+    assert result.column(0) == pa.array([3.0])
+
+    result = (
+        df.aggregate([column("b")], [median(column("a")).alias("median")])
+        .sort(column("b").sort())
+        .to_pydict()
+    )
+    assert result == {"b": ["x", "y"], "median": [1.5, 5.0]}
+
+
+def test_udaf_order_sensitive(ctx):
+    first_by = udaf(
+        FirstBy,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64(), pa.int64()],
+        volatility="immutable",
+        order_sensitive=True,
+    )
+    ctx.register_udaf(first_by)
+
+    batches = [
+        [pa.RecordBatch.from_pydict({"a": [1.0, 2.0], "b": [5, 3]})],
+        [pa.RecordBatch.from_pydict({"a": [3.0, 4.0], "b": [4, 2]})],
+    ]
+    df = ctx.create_dataframe(batches, name="t")
+
+    result = df.aggregate(
+        [], [first_by(column("a")).order_by(column("b")).build()]
+    ).collect()[0]
+    assert result.column(0) == pa.array([4.0])
+
+    result = ctx.sql("select firstby(a order by b) from t").collect()[0]
+    assert result.column(0) == pa.array([4.0])
+
+
+def test_udaf_ignores_order_by(df):
+    summarize = udaf(
+        Summarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+    )
+
+    result = df.aggregate(
+        [], [summarize(column("a")).order_by(column("b")).build()]
+    ).collect()[0]
+    assert result.column(0) == pa.array([1.0 + 2.0 + 3.0])
+
+
+def test_udaf_sliding_window(df):
+    summarize = udaf(
+        RetractableSummarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+    )
+    window = Window(
+        order_by=[column("a")],
+        window_frame=WindowFrame("rows", 1, 0),
+    )
+
+    result = df.select(
+        column("a"), summarize(column("a")).over(window).alias("total")
+    ).sort(column("a").sort())
+
+    assert result.to_pydict()["total"] == [1.0, 1.0 + 2.0, 2.0 + 3.0]
+
+
+def test_udaf_sliding_window_requires_retract(df):
+    summarize = udaf(
+        Summarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+    )
+    window = Window(
+        order_by=[column("a")],
+        window_frame=WindowFrame("rows", 1, 0),
+    )
+
+    with pytest.raises(Exception, match="retract_batch"):
+        df.select(summarize(column("a")).over(window)).collect()
+
+
+def test_udaf_pycapsule_round_trip(ctx, df):
+    summarize = udaf(
+        Summarize,
+        pa.float64(),
+        pa.float64(),
+        [pa.float64()],
+        volatility="immutable",
+    )
+
+    foreign = AggregateUDF.from_pycapsule(summarize)
+    result = df.aggregate([column("b")], [foreign(column("a"))]).sort(column("b"))
+    result = pa.Table.from_batches(result.collect())
+    assert result.column(1) == pa.chunked_array([[1.0 + 2.0, 3.0]])
+
+    capsule = summarize.__datafusion_aggregate_udf__()
+    ctx.register_udaf(AggregateUDF.from_pycapsule(capsule))
+    df_result = ctx.sql("select summarize(b) from test_table")
+    assert df_result.collect()[0][0][0].as_py() == 14.0
@@ -15,8 +15,9 @@
 # specific language governing permissions and limitations
 # under the License.
 
-from datafusion import udf, column
+from datafusion import ScalarUDF, SessionContext, udf, column, literal
 import pyarrow as pa
+import pyarrow.compute as pc
 import pytest
 
 
@@ -94,3 +95,152 @@ def test_udf_with_parameters(df) -> None:
     result = df2.collect()[0].column(0)
 
     assert result == pa.array([False, True, True])
+
+
+def test_udf_scalar_args(df) -> None:
+    seen_types = []
+
+    def over_threshold(values: pa.Array, threshold: pa.Scalar) -> pa.Array:
+        seen_types.append(type(threshold))
+        return pc.greater_equal(values, threshold)
+
+    over = udf(
+        over_threshold,
+        [pa.int64(), pa.int64()],
+        pa.bool_(),
+        volatility="immutable",
+        scalar_args=True,
+    )
+
+    result = df.select(over(column("a"), literal(2))).collect()[0].column(0)
+
+    assert result == pa.array([False, True, True])
+    assert seen_types
+    assert all(issubclass(t, pa.Scalar) for t in seen_types)
+
+
+def test_udf_scalar_args_scalar_result(df) -> None:
+    def add(left, right):
+        if isinstance(left, pa.Scalar) and isinstance(right, pa.Scalar):
+            return pa.scalar(left.as_py() + right.as_py(), type=pa.int64())
+        return pc.add(left, right)
+
+    add_udf = udf(
+        add,
+        [pa.int64(), pa.int64()],
+        pa.int64(),
+        volatility="volatile",
+        scalar_args=True,
+    )
+
+    result = df.select(add_udf(literal(1), literal(2)).alias("c")).collect()
+    assert result[0].column(0) == pa.array([3, 3, 3])
+
+    result = df.select(add_udf(column("a"), literal(10)).alias("c")).collect()
+    assert result[0].column(0) == pa.array([11, 12, 13])
+
+
+def test_udf_default_mode_expands_scalars(df) -> None:
+    def identity(values: pa.Array) -> pa.Array:
+        assert isinstance(values, pa.Array)
+        return values
+
+    identity_udf = udf(identity, [pa.int64()], pa.int64(), volatility="volatile")
+    result = df.select(identity_udf(literal(7))).collect()[0].column(0)
+
+    assert result == pa.array([7, 7, 7])
+
+
+def test_udf_dynamic_types(ctx, df) -> None:
+    def coerce_types(arg_types):
+        if len(arg_types) != 1:
+            raise ValueError("double expects exactly one argument")
+        (arg_type,) = arg_types
+        if pa.types.is_integer(arg_type):
+            return [pa.int64()]
+        if pa.types.is_floating(arg_type):
+            return [pa.float64()]
+        raise TypeError(f"double does not support {arg_type}")
+
+    double = udf(
+        lambda values: pc.multiply(values, 2),
+        coerce_types,
+        lambda arg_types: arg_types[0],
+        volatility="immutable",
+        name="double",
+    )
+
+    result = df.select(
+        double(column("a")).alias("int"),
+        double(column("a").cast(pa.int32())).alias("small_int"),
+        double(column("b").cast(pa.float32())).alias("float"),
+    ).collect()[0]
+
+    assert result.schema.field("int").type == pa.int64()
+    assert result.schema.field("small_int").type == pa.int64()
+    assert result.schema.field("float").type == pa.float64()
+    assert result.column(0) == pa.array([2, 4, 6])
+    assert result.column(2) == pa.array([8.0, 8.0, 12.0])
+
+    ctx.register_udf(double)
+    with pytest.raises(Exception, match="does not support"):
+        ctx.sql("SELECT double('x')").collect()
+    with pytest.raises(Exception, match="exactly one argument"):
+        ctx.sql("SELECT double(a, b) FROM test_table").collect()
+
+
+def test_udf_return_type_callback(df) -> None:
+    def first(values: pa.Array, _other: pa.Array) -> pa.Array:
+        return values
+
+    first_udf = udf(
+        first,
+        [pa.int64(), pa.int64()],
+        lambda arg_types: arg_types[0],
+        volatility="immutable",
+    )
+
+    result = df.select(first_udf(column("a"), column("b"))).collect()[0]
+    assert result.schema.field(0).type == pa.int64()
+    assert result.column(0) == pa.array([1, 2, 3])
+
+
+def test_udf_from_pycapsule_requires_capsule():
+    class NotAPlugin:
+        def __datafusion_scalar_udf__(self):
+            return "not a capsule"
+
+    with pytest.raises(TypeError):
+        ScalarUDF.from_pycapsule(NotAPlugin())
+
+    with pytest.raises(TypeError):
+        ScalarUDF.from_pycapsule(object())
+
+    # a capsule with a different interface is rejected
+    stream = SessionContext().from_pydict({"a": [1]}).__arrow_c_stream__()
+    with pytest.raises(ValueError, match="datafusion_scalar_udf"):
+        ScalarUDF.from_pycapsule(stream)
+
+
+def test_udf_pycapsule_round_trip(ctx, df) -> None:
+    is_null = udf(
+        lambda x: x.is_null(),
+        [pa.int64()],
+        pa.bool_(),
+        volatility="immutable",
+        name="is_null",
+    )
+
+    class Plugin:
+        def __datafusion_scalar_udf__(self):
+            return is_null.__datafusion_scalar_udf__()
+
+    # both objects providing a capsule and the capsule itself are accepted
+    for source in [is_null, Plugin(), is_null.__datafusion_scalar_udf__()]:
+        foreign = ScalarUDF.from_pycapsule(source)
+        result = df.select(foreign(column("a"))).collect()[0].column(0)
+        assert result == pa.array([False, False, False])
+
+    ctx.register_udf(ScalarUDF.from_pycapsule(is_null))
+    result = ctx.sql("select is_null(b) from test_table").collect()[0].column(0)
+    assert result == pa.array([False, False, False])
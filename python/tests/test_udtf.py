@@ -0,0 +1,84 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+import pyarrow as pa
+import pytest
+
+from datafusion import TableFunction, udtf
+from datafusion.catalog import TableProvider
+
+
+def repeat(value, count):
+    value = value.python_value().as_py()
+    count = count.python_value().as_py()
+    return [pa.RecordBatch.from_pydict({"v": [value] * count})]
+
+
+def test_udtf_returning_batches(ctx):
+    func = udtf(repeat)
+    assert isinstance(func, TableFunction)
+    ctx.register_udtf(func)
+
+    result = ctx.sql("SELECT v, count(*) AS n FROM repeat('a', 3) GROUP BY v")
+    assert result.to_pydict() == {"v": ["a"], "n": [3]}
+
+    # every call of the function is planned with its own arguments
+    result = ctx.sql("SELECT * FROM repeat('b', 2)")
+    assert result.to_pydict() == {"v": ["b", "b"]}
+
+
+def test_udtf_returning_arrow_stream(ctx):
+    def numbers(stop):
+        stop = stop.python_value().as_py()
+        schema = pa.schema([("n", pa.int64())])
+        batches = (pa.RecordBatch.from_pydict({"n": [i]}, schema) for i in range(stop))
+        return pa.RecordBatchReader.from_batches(schema, batches)
+
+    ctx.register_udtf(udtf(numbers, name="numbers"))
+    result = ctx.sql("SELECT sum(n) AS total FROM numbers(5)").to_pydict()
+    assert result == {"total": [10]}
+
+
+def test_udtf_returning_table_provider(ctx):
+    class Constant(TableProvider):
+        def __init__(self, value):
+            self.value = value
+
+        def schema(self):
+            return pa.schema([("value", pa.int64())])
+
+        def scan(self, projection, filters, limit):
+            return [pa.RecordBatch.from_pydict({"value": [self.value]})]
+
+    ctx.register_udtf(
+        udtf(lambda arg: Constant(arg.python_value().as_py()), name="constant")
+    )
+    assert ctx.sql("SELECT * FROM constant(42)").to_pydict() == {"value": [42]}
+
+
+def test_udtf_error(ctx):
+    def broken():
+        raise ValueError("cannot produce table")
+
+    ctx.register_udtf(udtf(broken, name="broken"))
+    with pytest.raises(Exception, match="cannot produce table"):
+        ctx.sql("SELECT * FROM broken()")
+
+
+def test_udtf_not_callable():
+    with pytest.raises(TypeError, match="must be callable"):
+        udtf(42)
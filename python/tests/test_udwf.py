@@ -18,7 +18,7 @@
 import pyarrow as pa
 import pytest
 
-from datafusion import SessionContext, column, udwf, lit, functions as f
+from datafusion import SessionContext, WindowUDF, column, udwf, lit, functions as f
 from datafusion.udf import WindowEvaluator
 from datafusion.expr import WindowFrame
 
@@ -304,3 +304,25 @@ def test_udwf_functions(df, name, expr, expected):
     result = df.sort(column("a")).select(column(name)).collect()[0]
 
     assert result.column(0) == pa.array(expected)
+
+
+def test_udwf_pycapsule_round_trip(df):
+    foreign_default = WindowUDF.from_pycapsule(smooth_default)
+    foreign_bounded = WindowUDF.from_pycapsule(smooth_bounded)
+    foreign_rank = WindowUDF.from_pycapsule(smooth_rank.__datafusion_window_udf__())
+    foreign_frame = WindowUDF.from_pycapsule(smooth_frame)
+
+    exprs = [
+        foreign_default(column("a")).partition_by(column("c")).build(),
+        foreign_bounded(column("a")),
+        foreign_rank(column("c")).order_by(column("c")).build(),
+        foreign_frame(column("a")).window_frame(WindowFrame("rows", None, 0)).build(),
+    ]
+    rounded = [f.round(e, lit(3)).alias(str(i)) for i, e in enumerate(exprs)]
+    df = df.select("a", *rounded)
+    result = df.sort(column("a")).collect()[0]
+
+    assert result.column(1) == pa.array([0, 0.9, 1.89, 2.889, 4.0, 4.9, 5.89])
+    assert result.column(2) == pa.array([0, 0.9, 1.9, 2.9, 3.9, 4.9, 5.9])
+    assert result.column(3) == pa.array([1, 1, 1, 1, 1.9, 2, 2])
+    assert result.column(4) == pa.array([0.0, 0.9, 1.89, 2.889, 3.889, 4.889, 5.889])
@@ -15,18 +15,25 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::any::Any;
 use std::collections::HashSet;
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use pyo3::exceptions::PyKeyError;
 use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
 
 use crate::errors::DataFusionError;
-use crate::utils::wait_for_future;
+use crate::ffi::{FFI_TableProvider, ForeignTableProvider, TABLE_PROVIDER_CAPSULE_NAME};
+use crate::table_provider::PythonTableProvider;
+use crate::utils::{export_capsule, foreign_capsule, wait_for_future};
 use datafusion::{
     arrow::pyarrow::ToPyArrow,
     catalog::{CatalogProvider, SchemaProvider},
+    catalog_common::{MemoryCatalogProvider, MemorySchemaProvider},
     datasource::{TableProvider, TableType},
+    error::{DataFusionError as InnerDataFusionError, Result as DFResult},
 };
 
 #[pyclass(name = "Catalog", module = "datafusion", subclass)]
@@ -68,6 +75,12 @@ impl PyTable {
 
 #[pymethods]
 impl PyCatalog {
+    /// Create a new, empty in-memory catalog
+    #[staticmethod]
+    fn memory_catalog() -> Self {
+        Self::new(Arc::new(MemoryCatalogProvider::new()))
+    }
+
     fn names(&self) -> Vec<String> {
         self.catalog.schema_names()
     }
@@ -82,6 +95,26 @@ impl PyCatalog {
         }
     }
 
+    /// Add a database, either a `Database` or a python object implementing
+    /// the `datafusion.catalog.SchemaProvider` interface, to this catalog
+    fn register_schema(&self, name: &str, schema: &Bound<'_, PyAny>) -> PyResult<()> {
+        let schema = schema_provider_from_python(schema)?;
+        self.catalog
+            .register_schema(name, schema)
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
+    /// Remove a database from this catalog. If `cascade` is false, only empty
+    /// databases can be removed.
+    #[pyo3(signature = (name, cascade=true))]
+    fn deregister_schema(&self, name: &str, cascade: bool) -> PyResult<()> {
+        self.catalog
+            .deregister_schema(name, cascade)
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!(
             "Catalog(schema_names=[{}])",
@@ -92,6 +125,12 @@ impl PyCatalog {
 
 #[pymethods]
 impl PyDatabase {
+    /// Create a new, empty in-memory database
+    #[staticmethod]
+    fn memory_schema() -> Self {
+        Self::new(Arc::new(MemorySchemaProvider::new()))
+    }
+
     fn names(&self) -> HashSet<String> {
         self.database.table_names().into_iter().collect()
     }
@@ -104,19 +143,61 @@ impl PyDatabase {
         }
     }
 
+    /// Add a table, either a `Table` or a python object implementing the
+    /// `datafusion.catalog.TableProvider` interface, to this database
+    fn register_table(&self, name: &str, table: &Bound<'_, PyAny>) -> PyResult<()> {
+        let table = table_provider_from_python(table)?;
+        self.database
+            .register_table(name.to_string(), table)
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
+    fn deregister_table(&self, name: &str) -> PyResult<()> {
+        self.database
+            .deregister_table(name)
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!(
             "Database(table_names=[{}])",
             Vec::from_iter(self.names()).join(";")
         ))
     }
-
-    // register_table
-    // deregister_table
 }
 
 #[pymethods]
 impl PyTable {
+    /// Create a table from a python object implementing the
+    /// `datafusion.catalog.TableProvider` interface
+    #[staticmethod]
+    fn from_provider(provider: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let provider = PythonTableProvider::new(provider)?;
+        Ok(Self::new(Arc::new(provider)))
+    }
+
+    /// Create a table from a provider implemented in another Rust library. The
+    /// object must either be a `datafusion_table_provider` PyCapsule or provide
+    /// one from a `__datafusion_table_provider__` method.
+    #[staticmethod]
+    fn from_pycapsule(provider: Bound<'_, PyAny>) -> PyResult<Self> {
+        let capsule = foreign_capsule(provider, TABLE_PROVIDER_CAPSULE_NAME)?;
+        let provider = unsafe { &*(capsule.pointer() as *const FFI_TableProvider) };
+        Ok(Self::new(Arc::new(ForeignTableProvider::from(provider))))
+    }
+
+    /// Export the table in a `datafusion_table_provider` PyCapsule, to be used
+    /// by other libraries
+    fn __datafusion_table_provider__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        let provider = FFI_TableProvider::new(self.table.clone(), true);
+        export_capsule(py, provider, TABLE_PROVIDER_CAPSULE_NAME)
+    }
+
     /// Get a reference to the schema for this table
     #[getter]
     fn schema(&self, py: Python) -> PyResult<PyObject> {
@@ -137,9 +218,171 @@ impl PyTable {
         let kind = self.kind();
         Ok(format!("Table(kind={kind})"))
     }
+}
+
+/// Resolve a python table into a `TableProvider`. Accepts either a `Table`
+/// (internal or the python wrapper), an object providing a table provider of
+/// another Rust library or an object implementing the
+/// `datafusion.catalog.TableProvider` interface.
+pub(crate) fn table_provider_from_python(
+    table: &Bound<'_, PyAny>,
+) -> PyResult<Arc<dyn TableProvider>> {
+    if let Ok(table) = table.extract::<PyRef<PyTable>>() {
+        return Ok(table.table());
+    }
+    // `datafusion.catalog.Table` wraps the internal table
+    if let Ok(inner) = table.getattr("table") {
+        if let Ok(table) = inner.extract::<PyRef<PyTable>>() {
+            return Ok(table.table());
+        }
+    }
+    if table.hasattr("__datafusion_table_provider__")? {
+        return Ok(PyTable::from_pycapsule(table.clone())?.table());
+    }
+    Ok(Arc::new(PythonTableProvider::new(table)?))
+}
+
+/// Resolve a python database into a `SchemaProvider`. Accepts either a `Database`
+/// (internal or the python wrapper) or an object implementing the
+/// `datafusion.catalog.SchemaProvider` interface.
+pub(crate) fn schema_provider_from_python(
+    schema: &Bound<'_, PyAny>,
+) -> PyResult<Arc<dyn SchemaProvider>> {
+    if let Ok(database) = schema.extract::<PyRef<PyDatabase>>() {
+        return Ok(database.database.clone());
+    }
+    // `datafusion.catalog.Database` wraps the internal database
+    if let Ok(inner) = schema.getattr("db") {
+        if let Ok(database) = inner.extract::<PyRef<PyDatabase>>() {
+            return Ok(database.database.clone());
+        }
+    }
+    Ok(Arc::new(PythonSchemaProvider::new(schema.clone().unbind())))
+}
+
+/// Resolve a python catalog into a `CatalogProvider`. Accepts either a `Catalog`
+/// (internal or the python wrapper) or an object implementing the
+/// `datafusion.catalog.CatalogProvider` interface.
+pub(crate) fn catalog_provider_from_python(
+    catalog: &Bound<'_, PyAny>,
+) -> PyResult<Arc<dyn CatalogProvider>> {
+    if let Ok(catalog) = catalog.extract::<PyRef<PyCatalog>>() {
+        return Ok(catalog.catalog.clone());
+    }
+    // `datafusion.catalog.Catalog` wraps the internal catalog
+    if let Ok(inner) = catalog.getattr("catalog") {
+        if let Ok(catalog) = inner.extract::<PyRef<PyCatalog>>() {
+            return Ok(catalog.catalog.clone());
+        }
+    }
+    Ok(Arc::new(PythonCatalogProvider::new(
+        catalog.clone().unbind(),
+    )))
+}
+
+/// Report an exception raised by a python provider from a trait method that
+/// cannot return errors through `sys.unraisablehook`, which prints it by default
+pub(crate) fn report_error<T>(py: Python, provider: &PyObject, result: PyResult<T>) -> Option<T> {
+    result
+        .map_err(|err| err.write_unraisable_bound(py, Some(provider.bind(py))))
+        .ok()
+}
+
+// Wraps a python object implementing `datafusion.catalog.SchemaProvider`
+#[derive(Debug)]
+pub(crate) struct PythonSchemaProvider {
+    provider: PyObject,
+}
+
+impl PythonSchemaProvider {
+    pub fn new(provider: PyObject) -> Self {
+        Self { provider }
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for PythonSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        Python::with_gil(|py| {
+            let names = self
+                .provider
+                .bind(py)
+                .call_method0("table_names")
+                .and_then(|v| v.extract());
+            report_error(py, &self.provider, names).unwrap_or_default()
+        })
+    }
 
-    // fn scan
-    // fn statistics
-    // fn has_exact_statistics
-    // fn supports_filter_pushdown
+    /// Tables are looked up from python each time they are requested so that
+    /// the python provider can resolve them lazily at planning time.
+    async fn table(&self, name: &str) -> DFResult<Option<Arc<dyn TableProvider>>> {
+        Python::with_gil(|py| {
+            let table = self.provider.bind(py).call_method1("table", (name,))?;
+            if table.is_none() {
+                return Ok(None);
+            }
+            table_provider_from_python(&table).map(Some)
+        })
+        .map_err(|err| InnerDataFusionError::External(Box::new(err)))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        Python::with_gil(|py| {
+            let exists = self
+                .provider
+                .bind(py)
+                .call_method1("table_exist", (name,))
+                .and_then(|v| v.extract());
+            report_error(py, &self.provider, exists).unwrap_or(false)
+        })
+    }
+}
+
+// Wraps a python object implementing `datafusion.catalog.CatalogProvider`
+#[derive(Debug)]
+pub(crate) struct PythonCatalogProvider {
+    provider: PyObject,
+}
+
+impl PythonCatalogProvider {
+    pub fn new(provider: PyObject) -> Self {
+        Self { provider }
+    }
+}
+
+impl CatalogProvider for PythonCatalogProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        Python::with_gil(|py| {
+            let names = self
+                .provider
+                .bind(py)
+                .call_method0("schema_names")
+                .and_then(|v| v.extract());
+            report_error(py, &self.provider, names).unwrap_or_default()
+        })
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        Python::with_gil(|py| {
+            let schema = self
+                .provider
+                .bind(py)
+                .call_method1("schema", (name,))
+                .and_then(|schema| {
+                    if schema.is_none() {
+                        return Ok(None);
+                    }
+                    schema_provider_from_python(&schema).map(Some)
+                });
+            report_error(py, &self.provider, schema).flatten()
+        })
+    }
 }
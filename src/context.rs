@@ -16,14 +16,19 @@
 // under the License.
 
 use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use arrow::array::RecordBatchReader;
 use arrow::ffi_stream::ArrowArrayStreamReader;
+use arrow::json::reader::infer_json_schema_from_iterator;
+use arrow::json::ReaderBuilder;
 use arrow::pyarrow::FromPyArrow;
-use datafusion::execution::session_state::SessionStateBuilder;
+use datafusion::execution::session_state::{SessionState, SessionStateBuilder};
+use futures::future::try_join_all;
+use futures::TryStreamExt;
 use object_store::ObjectStore;
 use url::Url;
 use uuid::Uuid;
@@ -31,7 +36,11 @@ use uuid::Uuid;
 use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
 
-use crate::catalog::{PyCatalog, PyTable};
+use crate::catalog::{
+    catalog_provider_from_python, schema_provider_from_python, table_provider_from_python,
+    PyCatalog, PyTable,
+};
+use crate::csv_format::NullRegexCsvFormat;
 use crate::dataframe::PyDataFrame;
 use crate::dataset::Dataset;
 use crate::errors::{py_datafusion_err, DataFusionError};
@@ -40,28 +49,33 @@ use crate::physical_plan::PyExecutionPlan;
 use crate::record_batch::PyRecordBatchStream;
 use crate::sql::logical::PyLogicalPlan;
 use crate::store::StorageContexts;
+use crate::streaming_table::{arrow_stream_table, python_streaming_table};
 use crate::udaf::PyAggregateUDF;
 use crate::udf::PyScalarUDF;
+use crate::udtf::PyTableFunction;
 use crate::udwf::PyWindowUDF;
-use crate::utils::{get_tokio_runtime, wait_for_future};
-use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef};
+use crate::utils::{
+    future_into_py, get_tokio_runtime, set_tokio_worker_threads, single_byte, wait_for_future,
+};
+use datafusion::arrow::datatypes::{DataType, FieldRef, Schema, SchemaRef};
 use datafusion::arrow::pyarrow::PyArrowType;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::catalog_common::TableReference;
 use datafusion::common::{exec_err, ScalarValue};
+use datafusion::config::TableOptions;
 use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
-use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::listing::{
     ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
 };
 use datafusion::datasource::MemTable;
 use datafusion::datasource::TableProvider;
+use datafusion::error::DataFusionError as InnerDataFusionError;
 use datafusion::execution::context::{
     DataFilePaths, SQLOptions, SessionConfig, SessionContext, TaskContext,
 };
 use datafusion::execution::disk_manager::DiskManagerConfig;
 use datafusion::execution::memory_pool::{FairSpillPool, GreedyMemoryPool, UnboundedMemoryPool};
-use datafusion::execution::options::ReadOptions;
+use datafusion::execution::options::{ArrowReadOptions, ReadOptions};
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use datafusion::physical_plan::SendableRecordBatchStream;
 use datafusion::prelude::{
@@ -168,6 +182,8 @@ impl PySessionConfig {
 #[derive(Clone)]
 pub struct PyRuntimeConfig {
     pub config: RuntimeConfig,
+    /// Number of threads of the Tokio Runtime executing queries
+    pub worker_threads: Option<usize>,
 }
 
 #[pymethods]
@@ -176,50 +192,86 @@ impl PyRuntimeConfig {
     fn new() -> Self {
         Self {
             config: RuntimeConfig::default(),
+            worker_threads: None,
         }
     }
 
     fn with_disk_manager_disabled(&self) -> Self {
         let config = self.config.clone();
         let config = config.with_disk_manager(DiskManagerConfig::Disabled);
-        Self { config }
+        Self {
+            config,
+            worker_threads: self.worker_threads,
+        }
     }
 
     fn with_disk_manager_os(&self) -> Self {
         let config = self.config.clone();
         let config = config.with_disk_manager(DiskManagerConfig::NewOs);
-        Self { config }
+        Self {
+            config,
+            worker_threads: self.worker_threads,
+        }
     }
 
     fn with_disk_manager_specified(&self, paths: Vec<String>) -> Self {
         let config = self.config.clone();
         let paths = paths.iter().map(|s| s.into()).collect();
         let config = config.with_disk_manager(DiskManagerConfig::NewSpecified(paths));
-        Self { config }
+        Self {
+            config,
+            worker_threads: self.worker_threads,
+        }
     }
 
     fn with_unbounded_memory_pool(&self) -> Self {
         let config = self.config.clone();
         let config = config.with_memory_pool(Arc::new(UnboundedMemoryPool::default()));
-        Self { config }
+        Self {
+            config,
+            worker_threads: self.worker_threads,
+        }
     }
 
     fn with_fair_spill_pool(&self, size: usize) -> Self {
         let config = self.config.clone();
         let config = config.with_memory_pool(Arc::new(FairSpillPool::new(size)));
-        Self { config }
+        Self {
+            config,
+            worker_threads: self.worker_threads,
+        }
     }
 
     fn with_greedy_memory_pool(&self, size: usize) -> Self {
         let config = self.config.clone();
         let config = config.with_memory_pool(Arc::new(GreedyMemoryPool::new(size)));
-        Self { config }
+        Self {
+            config,
+            worker_threads: self.worker_threads,
+        }
     }
 
     fn with_temp_file_path(&self, path: &str) -> Self {
         let config = self.config.clone();
         let config = config.with_temp_file_path(path);
-        Self { config }
+        Self {
+            config,
+            worker_threads: self.worker_threads,
+        }
+    }
+
+    /// Set the number of worker threads of the Tokio Runtime. The runtime is
+    /// shared by all contexts, so this must be set before the first query runs.
+    fn with_worker_threads(&self, threads: usize) -> PyResult<Self> {
+        if threads == 0 {
+            return Err(PyValueError::new_err(
+                "The number of worker threads must be greater than 0",
+            ));
+        }
+        Ok(Self {
+            config: self.config.clone(),
+            worker_threads: Some(threads),
+        })
     }
 }
 
@@ -283,6 +335,9 @@ impl PySessionContext {
             SessionConfig::default().with_information_schema(true)
         };
         let runtime_config = if let Some(c) = runtime {
+            if let Some(threads) = c.worker_threads {
+                set_tokio_worker_threads(threads)?;
+            }
             c.config
         } else {
             RuntimeConfig::default()
@@ -329,20 +384,73 @@ impl PySessionContext {
 
     #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (name, path, table_partition_cols=vec![],
-    file_extension=".parquet",
+    file_extension=None,
     schema=None,
-    file_sort_order=None))]
+    file_sort_order=None,
+    file_format="parquet",
+    format_options=HashMap::new()))]
     pub fn register_listing_table(
         &mut self,
         name: &str,
-        path: &str,
+        path: &Bound<'_, PyAny>,
         table_partition_cols: Vec<(String, String)>,
-        file_extension: &str,
+        file_extension: Option<&str>,
         schema: Option<PyArrowType<Schema>>,
         file_sort_order: Option<Vec<Vec<PySortExpr>>>,
+        file_format: &str,
+        format_options: HashMap<String, String>,
         py: Python,
     ) -> PyResult<()> {
-        let options = ListingOptions::new(Arc::new(ParquetFormat::new()))
+        let is_list = path.is_instance_of::<PyList>();
+        let paths = if is_list {
+            path.extract::<Vec<String>>()?
+        } else {
+            vec![path.extract::<String>()?]
+        };
+        if paths.is_empty() {
+            return Err(PyValueError::new_err("No table paths were provided"));
+        }
+
+        let state = self.ctx.state();
+        let format_factory = state.get_file_format_factory(file_format).ok_or_else(|| {
+            PyValueError::new_err(format!("Unsupported file format '{file_format}'"))
+        })?;
+        // options are given as in the OPTIONS clause of CREATE EXTERNAL TABLE
+        let format_options = format_options
+            .into_iter()
+            .map(|(key, value)| match key.starts_with("format.") {
+                true => (key, value),
+                false => (format!("format.{key}"), value),
+            })
+            .collect();
+        let format = format_factory
+            .create(&state, &format_options)
+            .map_err(DataFusionError::from)?;
+        let table_paths = paths
+            .iter()
+            .map(ListingTableUrl::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        // the files named in the paths are registered whatever their extension, while
+        // only the files of the format are read from directories
+        let file_extension = match file_extension {
+            Some(file_extension) => file_extension.to_owned(),
+            None if table_paths.iter().all(|path| !path.is_collection()) => String::new(),
+            None => {
+                let file_extension = format!(".{}", format_factory.get_ext());
+                let mismatch = table_paths.iter().find(|path| {
+                    !path.is_collection() && !path.as_str().ends_with(&file_extension)
+                });
+                if let Some(path) = mismatch {
+                    return Err(PyValueError::new_err(format!(
+                        "File path '{path}' does not match the extension '{file_extension}' \
+                         of the files read from directories, pass file_extension to read it"
+                    )));
+                }
+                file_extension
+            }
+        };
+
+        let options = ListingOptions::new(format)
             .with_file_extension(file_extension)
             .with_table_partition_cols(convert_table_partition_cols(table_partition_cols)?)
             .with_file_sort_order(
@@ -352,25 +460,26 @@ impl PySessionContext {
                     .map(|e| e.into_iter().map(|f| f.into()).collect())
                     .collect(),
             );
-        let table_path = ListingTableUrl::parse(path)?;
         let resolved_schema: SchemaRef = match schema {
             Some(s) => Arc::new(s.0),
             None => {
-                let state = self.ctx.state();
-                let schema = options.infer_schema(&state, &table_path);
-                wait_for_future(py, schema).map_err(DataFusionError::from)?
+                let schemas = table_paths
+                    .iter()
+                    .map(|table_path| options.infer_schema(&state, table_path));
+                let schemas =
+                    wait_for_future(py, try_join_all(schemas)).map_err(DataFusionError::from)?;
+                let schema = Schema::try_merge(schemas.iter().map(|s| s.as_ref().clone()))
+                    .map_err(DataFusionError::from)?;
+                Arc::new(schema)
             }
         };
-        let config = ListingTableConfig::new(table_path)
+        let config = ListingTableConfig::new_with_multi_paths(table_paths)
             .with_listing_options(options)
             .with_schema(resolved_schema);
         let table = ListingTable::try_new(config)?;
-        self.register_table(
-            name,
-            &PyTable {
-                table: Arc::new(table),
-            },
-        )?;
+        self.ctx
+            .register_table(name, Arc::new(table))
+            .map_err(DataFusionError::from)?;
         Ok(())
     }
 
@@ -398,6 +507,30 @@ impl PySessionContext {
         Ok(PyDataFrame::new(df))
     }
 
+    /// Returns an awaitable that resolves to a PyDataFrame whose plan corresponds
+    /// to the SQL statement.
+    #[pyo3(signature = (query, options=None))]
+    pub fn sql_async<'py>(
+        &self,
+        query: String,
+        options: Option<PySQLOptions>,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let options = if let Some(options) = options {
+            options.options
+        } else {
+            SQLOptions::new()
+        };
+        let ctx = self.ctx.clone();
+        future_into_py(py, async move {
+            let df = ctx
+                .sql_with_options(&query, options)
+                .await
+                .map_err(DataFusionError::from)?;
+            Ok(PyDataFrame::new(df))
+        })
+    }
+
     #[pyo3(signature = (partitions, name=None, schema=None))]
     pub fn create_dataframe(
         &mut self,
@@ -414,26 +547,7 @@ impl PySessionContext {
 
         let table = MemTable::try_new(schema, partitions.0).map_err(DataFusionError::from)?;
 
-        // generate a random (unique) name for this table if none is provided
-        // table name cannot start with numeric digit
-        let table_name = match name {
-            Some(val) => val.to_owned(),
-            None => {
-                "c".to_owned()
-                    + Uuid::new_v4()
-                        .simple()
-                        .encode_lower(&mut Uuid::encode_buffer())
-            }
-        };
-
-        self.ctx
-            .register_table(&*table_name, Arc::new(table))
-            .map_err(DataFusionError::from)?;
-
-        let table = wait_for_future(py, self._table(&table_name)).map_err(DataFusionError::from)?;
-
-        let df = PyDataFrame::new(table);
-        Ok(df)
+        self.dataframe_from_table(name, Arc::new(table), py)
     }
 
     /// Create a DataFrame from an existing logical plan
@@ -457,7 +571,7 @@ impl PySessionContext {
         let table = table_class.call_method1("from_pylist", args)?;
 
         // Convert Arrow Table to datafusion DataFrame
-        let df = self.from_arrow(table, name, py)?;
+        let df = self.from_arrow(table, name, false, py)?;
         Ok(df)
     }
 
@@ -477,18 +591,24 @@ impl PySessionContext {
         let table = table_class.call_method1("from_pydict", args)?;
 
         // Convert Arrow Table to datafusion DataFrame
-        let df = self.from_arrow(table, name, py)?;
+        let df = self.from_arrow(table, name, false, py)?;
         Ok(df)
     }
 
     /// Construct datafusion dataframe from Arrow Table
-    #[pyo3(signature = (data, name=None))]
+    #[pyo3(signature = (data, name=None, streaming=false))]
     pub fn from_arrow(
         &mut self,
         data: Bound<'_, PyAny>,
         name: Option<&str>,
+        streaming: bool,
         py: Python,
     ) -> PyResult<PyDataFrame> {
+        if streaming {
+            let table = arrow_stream_table(&data)?;
+            return self.dataframe_from_table(name, table, py);
+        }
+
         let (schema, batches) =
             if let Ok(stream_reader) = ArrowArrayStreamReader::from_pyarrow_bound(&data) {
                 // Works for any object that implements __arrow_c_stream__ in pycapsule.
@@ -533,7 +653,7 @@ impl PySessionContext {
         let table = table_class.call_method1("from_pandas", args)?;
 
         // Convert Arrow Table to datafusion DataFrame
-        let df = self.from_arrow(table, name, py)?;
+        let df = self.from_arrow(table, name, false, py)?;
         Ok(df)
     }
 
@@ -548,13 +668,13 @@ impl PySessionContext {
         let table = data.call_method0("to_arrow")?;
 
         // Convert Arrow Table to datafusion DataFrame
-        let df = self.from_arrow(table, name, data.py())?;
+        let df = self.from_arrow(table, name, false, data.py())?;
         Ok(df)
     }
 
-    pub fn register_table(&mut self, name: &str, table: &PyTable) -> PyResult<()> {
+    pub fn register_table(&mut self, name: &str, table: &Bound<'_, PyAny>) -> PyResult<()> {
         self.ctx
-            .register_table(name, table.table())
+            .register_table(name, table_provider_from_python(table)?)
             .map_err(DataFusionError::from)?;
         Ok(())
     }
@@ -579,13 +699,45 @@ impl PySessionContext {
         Ok(())
     }
 
+    /// Register an object implementing `__arrow_c_stream__` as a table whose
+    /// batches are read lazily by the first query that scans it.
+    pub fn register_arrow_stream(&mut self, name: &str, data: Bound<'_, PyAny>) -> PyResult<()> {
+        let table = arrow_stream_table(&data)?;
+        self.ctx
+            .register_table(name, table)
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
+    /// Register a python iterable of record batches, or a callable returning one,
+    /// as a table whose batches are pulled lazily while a query executes.
+    pub fn register_batch_iterator(
+        &mut self,
+        name: &str,
+        batches: Bound<'_, PyAny>,
+        schema: PyArrowType<Schema>,
+    ) -> PyResult<()> {
+        let table = python_streaming_table(Arc::new(schema.0), &batches)?;
+        self.ctx
+            .register_table(name, Arc::new(table))
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (name, path, table_partition_cols=vec![],
                         parquet_pruning=true,
                         file_extension=".parquet",
                         skip_metadata=true,
                         schema=None,
-                        file_sort_order=None))]
+                        file_sort_order=None,
+                        enable_page_index=None,
+                        bloom_filter_on_read=None,
+                        pushdown_filters=None,
+                        reorder_filters=None,
+                        metadata_size_hint=None,
+                        binary_as_string=None,
+                        schema_force_view_types=None))]
     pub fn register_parquet(
         &mut self,
         name: &str,
@@ -596,6 +748,13 @@ impl PySessionContext {
         skip_metadata: bool,
         schema: Option<PyArrowType<Schema>>,
         file_sort_order: Option<Vec<Vec<PySortExpr>>>,
+        enable_page_index: Option<bool>,
+        bloom_filter_on_read: Option<bool>,
+        pushdown_filters: Option<bool>,
+        reorder_filters: Option<bool>,
+        metadata_size_hint: Option<usize>,
+        binary_as_string: Option<bool>,
+        schema_force_view_types: Option<bool>,
         py: Python,
     ) -> PyResult<()> {
         let mut options = ParquetReadOptions::default()
@@ -610,8 +769,20 @@ impl PySessionContext {
             .map(|e| e.into_iter().map(|f| f.into()).collect())
             .collect();
 
-        let result = self.ctx.register_parquet(name, path, options);
-        wait_for_future(py, result).map_err(DataFusionError::from)?;
+        let table_options = self.parquet_table_options(
+            enable_page_index,
+            bloom_filter_on_read,
+            pushdown_filters,
+            reorder_filters,
+            metadata_size_hint,
+            binary_as_string,
+            schema_force_view_types,
+        );
+        let result = self.parquet_table(path, options, table_options);
+        let table = wait_for_future(py, result).map_err(DataFusionError::from)?;
+        self.ctx
+            .register_table(name, Arc::new(table))
+            .map_err(DataFusionError::from)?;
         Ok(())
     }
 
@@ -623,7 +794,13 @@ impl PySessionContext {
                         delimiter=",",
                         schema_infer_max_records=1000,
                         file_extension=".csv",
-                        file_compression_type=None))]
+                        file_compression_type=None,
+                        quote="\"",
+                        escape=None,
+                        comment=None,
+                        terminator=None,
+                        newlines_in_values=false,
+                        null_regex=None))]
     pub fn register_csv(
         &mut self,
         name: &str,
@@ -634,6 +811,12 @@ impl PySessionContext {
         schema_infer_max_records: usize,
         file_extension: &str,
         file_compression_type: Option<String>,
+        quote: &str,
+        escape: Option<&str>,
+        comment: Option<&str>,
+        terminator: Option<&str>,
+        newlines_in_values: bool,
+        null_regex: Option<&str>,
         py: Python,
     ) -> PyResult<()> {
         let delimiter = delimiter.as_bytes();
@@ -650,8 +833,26 @@ impl PySessionContext {
             .file_extension(file_extension)
             .file_compression_type(parse_file_compression_type(file_compression_type)?);
         options.schema = schema.as_ref().map(|x| &x.0);
-
-        if path.is_instance_of::<PyList>() {
+        options.quote = single_byte("Quote", quote)?;
+        options.escape = escape.map(|e| single_byte("Escape", e)).transpose()?;
+        options.comment = comment.map(|c| single_byte("Comment", c)).transpose()?;
+        options.terminator = terminator
+            .map(|t| single_byte("Terminator", t))
+            .transpose()?;
+        options.newlines_in_values = newlines_in_values;
+
+        if let Some(null_regex) = null_regex {
+            let paths = if path.is_instance_of::<PyList>() {
+                path.extract::<Vec<String>>()?
+            } else {
+                vec![path.extract::<String>()?]
+            };
+            let result = self.read_csv_with_null_regex(paths, options, null_regex);
+            let df = wait_for_future(py, result).map_err(DataFusionError::from)?;
+            self.ctx
+                .register_table(name, df.into_view())
+                .map_err(DataFusionError::from)?;
+        } else if path.is_instance_of::<PyList>() {
             let paths = path.extract::<Vec<String>>()?;
             let result = self.register_csv_from_multiple_paths(name, paths, options);
             wait_for_future(py, result).map_err(DataFusionError::from)?;
@@ -671,7 +872,9 @@ impl PySessionContext {
                         schema_infer_max_records=1000,
                         file_extension=".json",
                         table_partition_cols=vec![],
-                        file_compression_type=None))]
+                        file_compression_type=None,
+                        schema_hints=None,
+                        json_format="ndjson"))]
     pub fn register_json(
         &mut self,
         name: &str,
@@ -681,21 +884,28 @@ impl PySessionContext {
         file_extension: &str,
         table_partition_cols: Vec<(String, String)>,
         file_compression_type: Option<String>,
+        schema_hints: Option<PyArrowType<Schema>>,
+        json_format: &str,
         py: Python,
     ) -> PyResult<()> {
         let path = path
             .to_str()
             .ok_or_else(|| PyValueError::new_err("Unable to convert path to a string"))?;
 
-        let mut options = NdJsonReadOptions::default()
-            .file_compression_type(parse_file_compression_type(file_compression_type)?)
-            .table_partition_cols(convert_table_partition_cols(table_partition_cols)?);
-        options.schema_infer_max_records = schema_infer_max_records;
-        options.file_extension = file_extension;
-        options.schema = schema.as_ref().map(|x| &x.0);
-
-        let result = self.ctx.register_json(name, path, options);
-        wait_for_future(py, result).map_err(DataFusionError::from)?;
+        let table = self.json_table(
+            path,
+            schema.map(|x| x.0),
+            schema_hints.map(|x| x.0),
+            schema_infer_max_records,
+            file_extension,
+            table_partition_cols,
+            file_compression_type,
+            json_format,
+            py,
+        )?;
+        self.ctx
+            .register_table(name, table)
+            .map_err(DataFusionError::from)?;
 
         Ok(())
     }
@@ -730,6 +940,35 @@ impl PySessionContext {
         Ok(())
     }
 
+    #[pyo3(signature = (name,
+                        path,
+                        schema=None,
+                        file_extension=".arrow",
+                        table_partition_cols=vec![]))]
+    pub fn register_arrow(
+        &mut self,
+        name: &str,
+        path: PathBuf,
+        schema: Option<PyArrowType<Schema>>,
+        file_extension: &str,
+        table_partition_cols: Vec<(String, String)>,
+        py: Python,
+    ) -> PyResult<()> {
+        let path = path
+            .to_str()
+            .ok_or_else(|| PyValueError::new_err("Unable to convert path to a string"))?;
+
+        let mut options = ArrowReadOptions::default()
+            .table_partition_cols(convert_table_partition_cols(table_partition_cols)?);
+        options.file_extension = file_extension;
+        options.schema = schema.as_ref().map(|x| &x.0);
+
+        let result = self.ctx.register_arrow(name, path, options);
+        wait_for_future(py, result).map_err(DataFusionError::from)?;
+
+        Ok(())
+    }
+
     // Registers a PyArrow.Dataset
     pub fn register_dataset(
         &self,
@@ -751,6 +990,11 @@ impl PySessionContext {
         Ok(())
     }
 
+    pub fn register_udtf(&mut self, func: PyTableFunction) -> PyResult<()> {
+        self.ctx.register_udtf(&func.name, func.function);
+        Ok(())
+    }
+
     pub fn register_udaf(&mut self, udaf: PyAggregateUDF) -> PyResult<()> {
         self.ctx.register_udaf(udaf.function);
         Ok(())
@@ -772,6 +1016,36 @@ impl PySessionContext {
         }
     }
 
+    /// Register a catalog, either a `Catalog` or a python object implementing
+    /// the `datafusion.catalog.CatalogProvider` interface, under the given name
+    pub fn register_catalog(&self, name: &str, catalog: &Bound<'_, PyAny>) -> PyResult<()> {
+        let catalog = catalog_provider_from_python(catalog)?;
+        self.ctx.register_catalog(name, catalog);
+        Ok(())
+    }
+
+    /// Register a schema, either a `Database` or a python object implementing
+    /// the `datafusion.catalog.SchemaProvider` interface, in the default catalog
+    pub fn register_schema(&self, name: &str, schema: &Bound<'_, PyAny>) -> PyResult<()> {
+        let schema = schema_provider_from_python(schema)?;
+        let default_catalog = self
+            .ctx
+            .copied_config()
+            .options()
+            .catalog
+            .default_catalog
+            .clone();
+        let catalog = self.ctx.catalog(&default_catalog).ok_or_else(|| {
+            PyKeyError::new_err(format!(
+                "Catalog with name {default_catalog} doesn't exist."
+            ))
+        })?;
+        catalog
+            .register_schema(name, schema)
+            .map_err(DataFusionError::from)?;
+        Ok(())
+    }
+
     pub fn tables(&self) -> HashSet<String> {
         self.ctx
             .catalog_names()
@@ -806,7 +1080,7 @@ impl PySessionContext {
     }
 
     #[allow(clippy::too_many_arguments)]
-    #[pyo3(signature = (path, schema=None, schema_infer_max_records=1000, file_extension=".json", table_partition_cols=vec![], file_compression_type=None))]
+    #[pyo3(signature = (path, schema=None, schema_infer_max_records=1000, file_extension=".json", table_partition_cols=vec![], file_compression_type=None, schema_hints=None, json_format="ndjson"))]
     pub fn read_json(
         &mut self,
         path: PathBuf,
@@ -815,24 +1089,25 @@ impl PySessionContext {
         file_extension: &str,
         table_partition_cols: Vec<(String, String)>,
         file_compression_type: Option<String>,
+        schema_hints: Option<PyArrowType<Schema>>,
+        json_format: &str,
         py: Python,
     ) -> PyResult<PyDataFrame> {
         let path = path
             .to_str()
             .ok_or_else(|| PyValueError::new_err("Unable to convert path to a string"))?;
-        let mut options = NdJsonReadOptions::default()
-            .table_partition_cols(convert_table_partition_cols(table_partition_cols)?)
-            .file_compression_type(parse_file_compression_type(file_compression_type)?);
-        options.schema_infer_max_records = schema_infer_max_records;
-        options.file_extension = file_extension;
-        let df = if let Some(schema) = schema {
-            options.schema = Some(&schema.0);
-            let result = self.ctx.read_json(path, options);
-            wait_for_future(py, result).map_err(DataFusionError::from)?
-        } else {
-            let result = self.ctx.read_json(path, options);
-            wait_for_future(py, result).map_err(DataFusionError::from)?
-        };
+        let table = self.json_table(
+            path,
+            schema.map(|x| x.0),
+            schema_hints.map(|x| x.0),
+            schema_infer_max_records,
+            file_extension,
+            table_partition_cols,
+            file_compression_type,
+            json_format,
+            py,
+        )?;
+        let df = self.ctx.read_table(table).map_err(DataFusionError::from)?;
         Ok(PyDataFrame::new(df))
     }
 
@@ -845,7 +1120,13 @@ impl PySessionContext {
         schema_infer_max_records=1000,
         file_extension=".csv",
         table_partition_cols=vec![],
-        file_compression_type=None))]
+        file_compression_type=None,
+        quote="\"",
+        escape=None,
+        comment=None,
+        terminator=None,
+        newlines_in_values=false,
+        null_regex=None))]
     pub fn read_csv(
         &self,
         path: &Bound<'_, PyAny>,
@@ -856,6 +1137,12 @@ impl PySessionContext {
         file_extension: &str,
         table_partition_cols: Vec<(String, String)>,
         file_compression_type: Option<String>,
+        quote: &str,
+        escape: Option<&str>,
+        comment: Option<&str>,
+        terminator: Option<&str>,
+        newlines_in_values: bool,
+        null_regex: Option<&str>,
         py: Python,
     ) -> PyResult<PyDataFrame> {
         let delimiter = delimiter.as_bytes();
@@ -873,8 +1160,24 @@ impl PySessionContext {
             .table_partition_cols(convert_table_partition_cols(table_partition_cols)?)
             .file_compression_type(parse_file_compression_type(file_compression_type)?);
         options.schema = schema.as_ref().map(|x| &x.0);
-
-        if path.is_instance_of::<PyList>() {
+        options.quote = single_byte("Quote", quote)?;
+        options.escape = escape.map(|e| single_byte("Escape", e)).transpose()?;
+        options.comment = comment.map(|c| single_byte("Comment", c)).transpose()?;
+        options.terminator = terminator
+            .map(|t| single_byte("Terminator", t))
+            .transpose()?;
+        options.newlines_in_values = newlines_in_values;
+
+        if let Some(null_regex) = null_regex {
+            let paths = if path.is_instance_of::<PyList>() {
+                path.extract::<Vec<String>>()?
+            } else {
+                vec![path.extract::<String>()?]
+            };
+            let result = self.read_csv_with_null_regex(paths, options, null_regex);
+            let df = PyDataFrame::new(wait_for_future(py, result).map_err(DataFusionError::from)?);
+            Ok(df)
+        } else if path.is_instance_of::<PyList>() {
             let paths = path.extract::<Vec<String>>()?;
             let paths = paths.iter().map(|p| p as &str).collect::<Vec<&str>>();
             let result = self.ctx.read_csv(paths, options);
@@ -896,7 +1199,14 @@ impl PySessionContext {
         file_extension=".parquet",
         skip_metadata=true,
         schema=None,
-        file_sort_order=None))]
+        file_sort_order=None,
+        enable_page_index=None,
+        bloom_filter_on_read=None,
+        pushdown_filters=None,
+        reorder_filters=None,
+        metadata_size_hint=None,
+        binary_as_string=None,
+        schema_force_view_types=None))]
     pub fn read_parquet(
         &self,
         path: &str,
@@ -906,6 +1216,13 @@ impl PySessionContext {
         skip_metadata: bool,
         schema: Option<PyArrowType<Schema>>,
         file_sort_order: Option<Vec<Vec<PySortExpr>>>,
+        enable_page_index: Option<bool>,
+        bloom_filter_on_read: Option<bool>,
+        pushdown_filters: Option<bool>,
+        reorder_filters: Option<bool>,
+        metadata_size_hint: Option<usize>,
+        binary_as_string: Option<bool>,
+        schema_force_view_types: Option<bool>,
         py: Python,
     ) -> PyResult<PyDataFrame> {
         let mut options = ParquetReadOptions::default()
@@ -920,9 +1237,22 @@ impl PySessionContext {
             .map(|e| e.into_iter().map(|f| f.into()).collect())
             .collect();
 
-        let result = self.ctx.read_parquet(path, options);
-        let df = PyDataFrame::new(wait_for_future(py, result).map_err(DataFusionError::from)?);
-        Ok(df)
+        let table_options = self.parquet_table_options(
+            enable_page_index,
+            bloom_filter_on_read,
+            pushdown_filters,
+            reorder_filters,
+            metadata_size_hint,
+            binary_as_string,
+            schema_force_view_types,
+        );
+        let result = self.parquet_table(path, options, table_options);
+        let table = wait_for_future(py, result).map_err(DataFusionError::from)?;
+        let df = self
+            .ctx
+            .read_table(Arc::new(table))
+            .map_err(DataFusionError::from)?;
+        Ok(PyDataFrame::new(df))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -949,6 +1279,25 @@ impl PySessionContext {
         Ok(PyDataFrame::new(df))
     }
 
+    #[pyo3(signature = (path, schema=None, table_partition_cols=vec![], file_extension=".arrow"))]
+    pub fn read_arrow(
+        &self,
+        path: &str,
+        schema: Option<PyArrowType<Schema>>,
+        table_partition_cols: Vec<(String, String)>,
+        file_extension: &str,
+        py: Python,
+    ) -> PyResult<PyDataFrame> {
+        let mut options = ArrowReadOptions::default()
+            .table_partition_cols(convert_table_partition_cols(table_partition_cols)?);
+        options.file_extension = file_extension;
+        options.schema = schema.as_ref().map(|x| &x.0);
+
+        let result = self.ctx.read_arrow(path, options);
+        let df = wait_for_future(py, result).map_err(DataFusionError::from)?;
+        Ok(PyDataFrame::new(df))
+    }
+
     pub fn read_table(&self, table: &PyTable) -> PyResult<PyDataFrame> {
         let df = self
             .ctx
@@ -997,6 +1346,219 @@ impl PySessionContext {
         self.ctx.table(name).await
     }
 
+    /// Register `table` under `name`, or a random name if none is provided, and
+    /// return a DataFrame scanning it
+    fn dataframe_from_table(
+        &self,
+        name: Option<&str>,
+        table: Arc<dyn TableProvider>,
+        py: Python,
+    ) -> PyResult<PyDataFrame> {
+        // generate a random (unique) name for this table if none is provided
+        // table name cannot start with numeric digit
+        let table_name = match name {
+            Some(val) => val.to_owned(),
+            None => {
+                "c".to_owned()
+                    + Uuid::new_v4()
+                        .simple()
+                        .encode_lower(&mut Uuid::encode_buffer())
+            }
+        };
+
+        self.ctx
+            .register_table(&*table_name, table)
+            .map_err(DataFusionError::from)?;
+
+        let table = wait_for_future(py, self._table(&table_name)).map_err(DataFusionError::from)?;
+
+        let df = PyDataFrame::new(table);
+        Ok(df)
+    }
+
+    /// Create a table over JSON files, either line-delimited and scanned lazily or
+    /// holding a JSON array of objects and loaded whole into memory
+    #[allow(clippy::too_many_arguments)]
+    fn json_table(
+        &self,
+        path: &str,
+        schema: Option<Schema>,
+        schema_hints: Option<Schema>,
+        schema_infer_max_records: usize,
+        file_extension: &str,
+        table_partition_cols: Vec<(String, String)>,
+        file_compression_type: Option<String>,
+        json_format: &str,
+        py: Python,
+    ) -> PyResult<Arc<dyn TableProvider>> {
+        if schema.is_some() && schema_hints.is_some() {
+            return Err(PyValueError::new_err(
+                "schema and schema_hints cannot both be given",
+            ));
+        }
+        let file_compression_type = parse_file_compression_type(file_compression_type)?;
+        let table_path = ListingTableUrl::parse(path).map_err(DataFusionError::from)?;
+        match json_format {
+            "ndjson" => {
+                let mut options = NdJsonReadOptions::default()
+                    .file_compression_type(file_compression_type)
+                    .table_partition_cols(convert_table_partition_cols(table_partition_cols)?);
+                options.schema_infer_max_records = schema_infer_max_records;
+                options.file_extension = file_extension;
+
+                let session_config = self.ctx.copied_config();
+                let schema = match schema {
+                    Some(schema) => Arc::new(schema),
+                    None => {
+                        let result = options.get_resolved_schema(
+                            &session_config,
+                            self.ctx.state(),
+                            table_path.clone(),
+                        );
+                        let inferred =
+                            wait_for_future(py, result).map_err(DataFusionError::from)?;
+                        match schema_hints {
+                            Some(hints) => Arc::new(apply_schema_hints(&inferred, &hints)),
+                            None => inferred,
+                        }
+                    }
+                };
+                let listing_options =
+                    options.to_listing_options(&session_config, self.ctx.copied_table_options());
+                let config = ListingTableConfig::new(table_path)
+                    .with_listing_options(listing_options)
+                    .with_schema(schema);
+                let table = ListingTable::try_new(config).map_err(DataFusionError::from)?;
+                Ok(Arc::new(table))
+            }
+            "array" => {
+                if !table_partition_cols.is_empty() {
+                    return Err(PyValueError::new_err(
+                        "table_partition_cols are not supported for JSON array files",
+                    ));
+                }
+                let result = read_json_array(
+                    self.ctx.state(),
+                    table_path,
+                    schema,
+                    schema_hints,
+                    schema_infer_max_records,
+                    file_extension,
+                    file_compression_type,
+                );
+                let table = wait_for_future(py, result).map_err(DataFusionError::from)?;
+                Ok(Arc::new(table))
+            }
+            _ => Err(PyValueError::new_err(
+                "json_format must be one of: ndjson, array",
+            )),
+        }
+    }
+
+    /// Copy the table options of the session, overriding the options used to read
+    /// Parquet files that are given
+    #[allow(clippy::too_many_arguments)]
+    fn parquet_table_options(
+        &self,
+        enable_page_index: Option<bool>,
+        bloom_filter_on_read: Option<bool>,
+        pushdown_filters: Option<bool>,
+        reorder_filters: Option<bool>,
+        metadata_size_hint: Option<usize>,
+        binary_as_string: Option<bool>,
+        schema_force_view_types: Option<bool>,
+    ) -> TableOptions {
+        let mut table_options = self.ctx.copied_table_options();
+        let parquet = &mut table_options.parquet.global;
+        if let Some(enable_page_index) = enable_page_index {
+            parquet.enable_page_index = enable_page_index;
+        }
+        if let Some(bloom_filter_on_read) = bloom_filter_on_read {
+            parquet.bloom_filter_on_read = bloom_filter_on_read;
+        }
+        if let Some(pushdown_filters) = pushdown_filters {
+            parquet.pushdown_filters = pushdown_filters;
+        }
+        if let Some(reorder_filters) = reorder_filters {
+            parquet.reorder_filters = reorder_filters;
+        }
+        if metadata_size_hint.is_some() {
+            parquet.metadata_size_hint = metadata_size_hint;
+        }
+        if let Some(binary_as_string) = binary_as_string {
+            parquet.binary_as_string = binary_as_string;
+        }
+        if let Some(schema_force_view_types) = schema_force_view_types {
+            parquet.schema_force_view_types = schema_force_view_types;
+        }
+        table_options
+    }
+
+    /// Create a listing table over Parquet files read with `table_options` rather than
+    /// the table options of the session
+    async fn parquet_table(
+        &self,
+        path: &str,
+        options: ParquetReadOptions<'_>,
+        table_options: TableOptions,
+    ) -> datafusion::common::Result<ListingTable> {
+        let table_path = ListingTableUrl::parse(path)?;
+        if !path.ends_with(options.file_extension) && !table_path.is_collection() {
+            return exec_err!(
+                "File path '{path}' does not match the expected extension '{}'",
+                options.file_extension
+            );
+        }
+        let listing_options = options.to_listing_options(&self.ctx.copied_config(), table_options);
+        let schema = match options.schema {
+            Some(schema) => Arc::new(schema.clone()),
+            None => {
+                listing_options
+                    .infer_schema(&self.ctx.state(), &table_path)
+                    .await?
+            }
+        };
+        let config = ListingTableConfig::new(table_path)
+            .with_listing_options(listing_options)
+            .with_schema(schema);
+        ListingTable::try_new(config)
+    }
+
+    /// Read CSV files treating the values matching `null_regex` as nulls, which the
+    /// CSV options of DataFusion do not support
+    async fn read_csv_with_null_regex(
+        &self,
+        table_paths: Vec<String>,
+        options: CsvReadOptions<'_>,
+        null_regex: &str,
+    ) -> datafusion::common::Result<DataFrame> {
+        let table_paths = table_paths.to_urls()?;
+        if table_paths.is_empty() {
+            return exec_err!("No table paths were provided");
+        }
+        let format = NullRegexCsvFormat::new(&options, null_regex)?;
+        let schema = match options.schema {
+            Some(schema) => Arc::new(schema.clone()),
+            None => {
+                format
+                    .infer_schema_from_paths(
+                        &self.ctx.state(),
+                        &table_paths,
+                        options.file_extension,
+                    )
+                    .await?
+            }
+        };
+        let mut listing_options =
+            options.to_listing_options(&self.ctx.copied_config(), self.ctx.copied_table_options());
+        listing_options.format = Arc::new(format);
+        let config = ListingTableConfig::new_with_multi_paths(table_paths)
+            .with_listing_options(listing_options)
+            .with_schema(schema);
+        self.ctx
+            .read_table(Arc::new(ListingTable::try_new(config)?))
+    }
+
     async fn register_csv_from_multiple_paths(
         &self,
         name: &str,
@@ -1038,6 +1600,81 @@ impl PySessionContext {
     }
 }
 
+/// Replace the types of the inferred fields named in `hints`, appending the hinted
+/// fields missing from the inferred schema
+fn apply_schema_hints(inferred: &Schema, hints: &Schema) -> Schema {
+    let mut fields: Vec<FieldRef> = inferred
+        .fields()
+        .iter()
+        .map(|field| match hints.field_with_name(field.name()) {
+            Ok(hint) => Arc::new(hint.clone()),
+            Err(_) => Arc::clone(field),
+        })
+        .collect();
+    fields.extend(
+        hints
+            .fields()
+            .iter()
+            .filter(|hint| inferred.field_with_name(hint.name()).is_err())
+            .cloned(),
+    );
+    Schema::new_with_metadata(fields, inferred.metadata().clone())
+}
+
+/// Read files holding a JSON array of objects into memory. The JSON reader of
+/// DataFusion only supports line-delimited files.
+///
+/// Every file is fetched and parsed whole into `serde_json::Value`s, which are all
+/// kept until they are decoded into the batches of the returned `MemTable`.
+async fn read_json_array(
+    state: SessionState,
+    table_path: ListingTableUrl,
+    schema: Option<Schema>,
+    schema_hints: Option<Schema>,
+    schema_infer_max_records: usize,
+    file_extension: &str,
+    file_compression_type: FileCompressionType,
+) -> datafusion::common::Result<MemTable> {
+    let store = state.runtime_env().object_store(&table_path)?;
+    let files: Vec<_> = table_path
+        .list_all_files(&state, store.as_ref(), file_extension)
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut rows: Vec<serde_json::Value> = vec![];
+    for file in files {
+        let bytes = store.get(&file.location).await?.bytes().await?;
+        let reader = file_compression_type.convert_read(Cursor::new(bytes))?;
+        let values: Vec<serde_json::Value> = serde_json::from_reader(reader).map_err(|e| {
+            InnerDataFusionError::Execution(format!(
+                "{} is not a JSON array of objects: {e}",
+                file.location
+            ))
+        })?;
+        rows.extend(values);
+    }
+
+    let schema = match schema {
+        Some(schema) => schema,
+        None => {
+            let inferred = infer_json_schema_from_iterator(
+                rows.iter().take(schema_infer_max_records).map(Ok),
+            )?;
+            match schema_hints {
+                Some(hints) => apply_schema_hints(&inferred, &hints),
+                None => inferred,
+            }
+        }
+    };
+    let schema = Arc::new(schema);
+
+    let mut decoder = ReaderBuilder::new(Arc::clone(&schema)).build_decoder()?;
+    decoder.serialize(&rows)?;
+    let batches = decoder.flush()?.into_iter().collect();
+    MemTable::try_new(schema, vec![batches])
+}
+
 pub fn convert_table_partition_cols(
     table_partition_cols: Vec<(String, String)>,
 ) -> Result<Vec<(String, DataType)>, DataFusionError> {
@@ -0,0 +1,383 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Implements a Datafusion CSV file format that reads the values matching a regular
+/// expression as nulls, which the CSV options of Datafusion do not support. The files
+/// are decoded by the CSV reader of arrow, so the values are parsed the same way as
+/// in a plain CSV read.
+use std::any::Any;
+use std::sync::Arc;
+use std::task::Poll;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{ready, StreamExt, TryStreamExt};
+use object_store::{ObjectMeta, ObjectStore};
+use regex::Regex;
+use tokio_util::io::{StreamReader, SyncIoBridge};
+
+use datafusion::arrow::csv::reader::{Decoder, Format};
+use datafusion::arrow::csv::ReaderBuilder;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{plan_err, GetExt, Statistics};
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::datasource::physical_plan::{
+    FileMeta, FileOpenFuture, FileOpener, FileScanConfig, FileStream,
+};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::physical_expr::{EquivalenceProperties, PhysicalExpr};
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionMode, ExecutionPlan, Partitioning, PlanProperties,
+    SendableRecordBatchStream,
+};
+use datafusion::prelude::CsvReadOptions;
+
+/// CSV files whose values matching `null_regex` are read as nulls
+#[derive(Debug, Clone)]
+pub(crate) struct NullRegexCsvFormat {
+    format: Format,
+    file_compression_type: FileCompressionType,
+    schema_infer_max_records: usize,
+}
+
+impl NullRegexCsvFormat {
+    pub(crate) fn new(options: &CsvReadOptions<'_>, null_regex: &str) -> Result<Self> {
+        // a value is null only when the whole of it matches, empty values are nulls too
+        let null_regex = Regex::new(&format!("^(?:{null_regex})?$"))
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let mut format = Format::default()
+            .with_header(options.has_header)
+            .with_delimiter(options.delimiter)
+            .with_quote(options.quote)
+            .with_null_regex(null_regex);
+        if let Some(escape) = options.escape {
+            format = format.with_escape(escape);
+        }
+        if let Some(comment) = options.comment {
+            format = format.with_comment(comment);
+        }
+        if let Some(terminator) = options.terminator {
+            format = format.with_terminator(terminator);
+        }
+        Ok(Self {
+            format,
+            file_compression_type: options.file_compression_type,
+            schema_infer_max_records: options.schema_infer_max_records,
+        })
+    }
+
+    /// Infer the schema of the files found at `table_paths` from up to
+    /// `schema_infer_max_records` rows across all of them
+    pub(crate) async fn infer_schema_from_paths(
+        &self,
+        state: &SessionState,
+        table_paths: &[ListingTableUrl],
+        file_extension: &str,
+    ) -> Result<SchemaRef> {
+        let mut schemas = vec![];
+        let mut records_to_read = self.schema_infer_max_records;
+        for table_path in table_paths {
+            let store = state.runtime_env().object_store(table_path)?;
+            let files: Vec<_> = table_path
+                .list_all_files(state, store.as_ref(), file_extension)
+                .await?
+                .try_collect()
+                .await?;
+            schemas.extend(
+                self.infer_file_schemas(&store, &files, &mut records_to_read)
+                    .await?,
+            );
+        }
+        if schemas.is_empty() {
+            let paths: Vec<_> = table_paths.iter().map(|path| path.as_str()).collect();
+            return plan_err!(
+                "No files with the extension '{file_extension}' were found at {}",
+                paths.join(", ")
+            );
+        }
+        Ok(Arc::new(Schema::try_merge(schemas)?))
+    }
+
+    /// Infer the schemas of `objects` while `records_to_read` is not spent, the schema
+    /// of the first object is always inferred so that its header names the columns
+    async fn infer_file_schemas(
+        &self,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+        records_to_read: &mut usize,
+    ) -> Result<Vec<Schema>> {
+        let mut schemas = vec![];
+        for object in objects {
+            if *records_to_read == 0 && !schemas.is_empty() {
+                break;
+            }
+            // Stream the file so that only the bytes holding the inferred records
+            // are fetched, the rest of the stream is dropped with the reader
+            let stream = store.get(&object.location).await?.into_stream();
+            let reader = SyncIoBridge::new(StreamReader::new(stream));
+            let compression = self.file_compression_type;
+            let format = self.format.clone();
+            let max_records = *records_to_read;
+            let (schema, records_read) = tokio::task::spawn_blocking(move || {
+                let reader = compression.convert_read(reader)?;
+                Ok::<_, DataFusionError>(format.infer_schema(reader, Some(max_records))?)
+            })
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))??;
+            schemas.push(schema);
+            *records_to_read -= records_read;
+        }
+        Ok(schemas)
+    }
+}
+
+#[async_trait]
+impl FileFormat for NullRegexCsvFormat {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_ext(&self) -> String {
+        "csv".to_string()
+    }
+
+    fn get_ext_with_compression(
+        &self,
+        file_compression_type: &FileCompressionType,
+    ) -> Result<String> {
+        Ok(format!(
+            "{}{}",
+            self.get_ext(),
+            file_compression_type.get_ext()
+        ))
+    }
+
+    async fn infer_schema(
+        &self,
+        _state: &SessionState,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        let mut records_to_read = self.schema_infer_max_records;
+        let schemas = self
+            .infer_file_schemas(store, objects, &mut records_to_read)
+            .await?;
+        Ok(Arc::new(Schema::try_merge(schemas)?))
+    }
+
+    async fn infer_stats(
+        &self,
+        _state: &SessionState,
+        _store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        _object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&table_schema))
+    }
+
+    async fn create_physical_plan(
+        &self,
+        _state: &SessionState,
+        conf: FileScanConfig,
+        _filters: Option<&Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(NullRegexCsvExec::new(
+            conf,
+            self.format.clone(),
+            self.file_compression_type,
+        )))
+    }
+}
+
+/// Scans CSV files with a [`NullRegexCsvFormat`], one partition per file group
+#[derive(Debug)]
+struct NullRegexCsvExec {
+    base_config: FileScanConfig,
+    format: Format,
+    file_compression_type: FileCompressionType,
+    projected_statistics: Statistics,
+    metrics: ExecutionPlanMetricsSet,
+    plan_properties: PlanProperties,
+}
+
+impl NullRegexCsvExec {
+    fn new(
+        base_config: FileScanConfig,
+        format: Format,
+        file_compression_type: FileCompressionType,
+    ) -> Self {
+        let (projected_schema, projected_statistics, orderings) = base_config.project();
+        let plan_properties = PlanProperties::new(
+            EquivalenceProperties::new_with_orderings(projected_schema, &orderings),
+            Partitioning::UnknownPartitioning(base_config.file_groups.len()),
+            ExecutionMode::Bounded,
+        );
+        Self {
+            base_config,
+            format,
+            file_compression_type,
+            projected_statistics,
+            metrics: ExecutionPlanMetricsSet::new(),
+            plan_properties,
+        }
+    }
+
+    /// Indices of the projected columns read from the files, the partition columns
+    /// come after them
+    fn file_projection(&self) -> Option<Vec<usize>> {
+        let file_columns = self.base_config.file_schema.fields().len();
+        self.base_config.projection.as_ref().map(|projection| {
+            projection
+                .iter()
+                .copied()
+                .filter(|&index| index < file_columns)
+                .collect()
+        })
+    }
+}
+
+impl DisplayAs for NullRegexCsvExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "NullRegexCsvExec: ")?;
+        self.base_config.fmt_as(t, f)
+    }
+}
+
+impl ExecutionPlan for NullRegexCsvExec {
+    fn name(&self) -> &str {
+        "NullRegexCsvExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.plan_properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        // this is a leaf node and has no children
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let object_store = context
+            .runtime_env()
+            .object_store(&self.base_config.object_store_url)?;
+        let opener = NullRegexCsvOpener {
+            object_store,
+            format: self.format.clone(),
+            file_schema: Arc::clone(&self.base_config.file_schema),
+            file_projection: self.file_projection(),
+            batch_size: context.session_config().batch_size(),
+            file_compression_type: self.file_compression_type,
+        };
+        let stream = FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+        Ok(Box::pin(stream))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(self.projected_statistics.clone())
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// Opens whole CSV files, the scan is not split into byte ranges
+struct NullRegexCsvOpener {
+    object_store: Arc<dyn ObjectStore>,
+    format: Format,
+    file_schema: SchemaRef,
+    file_projection: Option<Vec<usize>>,
+    batch_size: usize,
+    file_compression_type: FileCompressionType,
+}
+
+impl FileOpener for NullRegexCsvOpener {
+    fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture> {
+        let mut builder = ReaderBuilder::new(Arc::clone(&self.file_schema))
+            .with_format(self.format.clone())
+            .with_batch_size(self.batch_size);
+        if let Some(projection) = &self.file_projection {
+            builder = builder.with_projection(projection.clone());
+        }
+        let decoder = builder.build_decoder();
+        let store = Arc::clone(&self.object_store);
+        let compression = self.file_compression_type;
+        Ok(Box::pin(async move {
+            let stream = store
+                .get(file_meta.location())
+                .await?
+                .into_stream()
+                .map_err(DataFusionError::from)
+                .boxed();
+            let input = compression.convert_stream(stream)?;
+            Ok(decode_stream(input, decoder))
+        }))
+    }
+}
+
+/// Decode the CSV bytes of `input` into record batches
+fn decode_stream(
+    input: BoxStream<'static, Result<Bytes>>,
+    mut decoder: Decoder,
+) -> BoxStream<'static, std::result::Result<RecordBatch, ArrowError>> {
+    let mut input = input.fuse();
+    let mut buffered = Bytes::new();
+    futures::stream::poll_fn(move |cx| {
+        loop {
+            if buffered.is_empty() {
+                match ready!(input.poll_next_unpin(cx)) {
+                    Some(Ok(bytes)) => buffered = bytes,
+                    Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                    None => {}
+                }
+            }
+            // the decoder is called with no bytes at the end of the input to
+            // delimit the last record
+            let decoded = match decoder.decode(&buffered) {
+                Ok(0) => break,
+                Ok(decoded) => decoded,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            };
+            buffered = buffered.slice(decoded..);
+        }
+        Poll::Ready(decoder.flush().transpose())
+    })
+    .boxed()
+}
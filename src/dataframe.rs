@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashMap;
 use std::ffi::CString;
 use std::sync::Arc;
 
@@ -23,28 +24,43 @@ use arrow::compute::can_cast_types;
 use arrow::error::ArrowError;
 use arrow::ffi::FFI_ArrowSchema;
 use arrow::ffi_stream::FFI_ArrowArrayStream;
+use arrow::ipc::writer::{FileWriter, IpcWriteOptions, StreamWriter};
+use arrow::ipc::CompressionType;
 use arrow::util::display::{ArrayFormatter, FormatOptions};
-use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::datatypes::{DataType, Schema};
 use datafusion::arrow::pyarrow::{PyArrowType, ToPyArrow};
 use datafusion::arrow::util::pretty;
-use datafusion::common::UnnestOptions;
-use datafusion::config::{CsvOptions, TableParquetOptions};
+use datafusion::common::{Column, TableReference, UnnestOptions};
+use datafusion::config::{ConfigField, CsvOptions, JsonOptions, TableParquetOptions};
 use datafusion::dataframe::{DataFrame, DataFrameWriteOptions};
+use datafusion::datasource::listing::{ListingTable, ListingTableUrl};
 use datafusion::execution::SendableRecordBatchStream;
+use datafusion::functions::expr_fn::to_char;
+use datafusion::logical_expr::dml::InsertOp;
 use datafusion::parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
 use datafusion::prelude::*;
+use futures::{StreamExt, TryStreamExt};
+use object_store::buffered::BufWriter;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
 use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
 use pyo3::types::{PyCapsule, PyTuple, PyTupleMethods};
+use tokio::io::AsyncWriteExt;
 use tokio::task::JoinHandle;
 
+use crate::cancellation::PyCancellationToken;
+use crate::context::parse_file_compression_type;
 use crate::errors::py_datafusion_err;
 use crate::expr::sort_expr::to_sort_expressions;
 use crate::physical_plan::PyExecutionPlan;
 use crate::record_batch::PyRecordBatchStream;
 use crate::sql::logical::PyLogicalPlan;
-use crate::utils::{get_tokio_runtime, wait_for_future};
+use crate::utils::{
+    future_into_py, get_tokio_runtime, single_byte, timeout_to_deadline, wait_for_future,
+    wait_for_future_cancellable,
+};
 use crate::{
     errors::DataFusionError,
     expr::{sort_expr::PySortExpr, PyExpr},
@@ -64,6 +80,325 @@ impl PyDataFrame {
     pub fn new(df: DataFrame) -> Self {
         Self { df: Arc::new(df) }
     }
+
+    /// Apply the options of a `write_*` call, returning the DataFrame, output
+    /// path and DataFusion write options to write with, along with the files to
+    /// delete once the write succeeded when overwriting
+    fn prepare_write(
+        &self,
+        path: &str,
+        options: Option<PyWriteOptions>,
+        file_extension: &str,
+        py: Python,
+    ) -> PyResult<(
+        DataFrame,
+        String,
+        DataFrameWriteOptions,
+        Option<PreviousOutput>,
+    )> {
+        let df = self.df.as_ref().clone();
+        let Some(options) = options else {
+            return Ok((df, path.to_string(), DataFrameWriteOptions::new(), None));
+        };
+
+        let df = if options.sort_by.is_empty() {
+            df
+        } else {
+            df.sort(to_sort_expressions(options.sort_by))?
+        };
+
+        // DataFusion writes a single file whenever the path has a file extension
+        // and does not end with a `/`, and a directory otherwise
+        let path = match options.single_file_output {
+            Some(true) => {
+                if !options.partition_by.is_empty() {
+                    return Err(PyValueError::new_err(
+                        "single_file_output can not be combined with partition_by",
+                    ));
+                }
+                if path.ends_with('/') || std::path::Path::new(path).extension().is_none() {
+                    return Err(PyValueError::new_err(format!(
+                        "single_file_output requires a path with a file extension, got {path}"
+                    )));
+                }
+                path.to_string()
+            }
+            Some(false) if !path.ends_with('/') => format!("{path}/"),
+            _ => path.to_string(),
+        };
+
+        let previous_output = if options.overwrite {
+            Some(wait_for_future(
+                py,
+                PreviousOutput::list(&df, &path, file_extension),
+            )?)
+        } else {
+            None
+        };
+
+        let write_options = DataFrameWriteOptions::new()
+            .with_single_file_output(options.single_file_output.unwrap_or(false))
+            .with_partition_by(options.partition_by);
+        Ok((df, path, write_options, previous_output))
+    }
+}
+
+/// Replace the date and timestamp columns of a DataFrame by strings formatted with
+/// `to_char`, leaving them untouched when no format is given for their type.
+fn format_temporal_columns(
+    df: DataFrame,
+    date_format: Option<&str>,
+    timestamp_format: Option<&str>,
+) -> Result<DataFrame, DataFusionError> {
+    if date_format.is_none() && timestamp_format.is_none() {
+        return Ok(df);
+    }
+    let exprs: Vec<Expr> = df
+        .schema()
+        .iter()
+        .map(|(qualifier, field)| {
+            let column = Expr::Column(Column::from((qualifier, field)));
+            let format = match field.data_type() {
+                DataType::Date32 | DataType::Date64 => date_format,
+                DataType::Timestamp(_, _) => timestamp_format,
+                _ => None,
+            };
+            match format {
+                Some(format) => to_char(column, lit(format)).alias(field.name()),
+                None => column,
+            }
+        })
+        .collect();
+    Ok(df.select(exprs)?)
+}
+
+/// Options shared by the `write_*` methods of a DataFrame
+#[derive(FromPyObject)]
+struct PyWriteOptions {
+    #[pyo3(item)]
+    partition_by: Vec<String>,
+    #[pyo3(item)]
+    single_file_output: Option<bool>,
+    #[pyo3(item)]
+    overwrite: bool,
+    #[pyo3(item)]
+    sort_by: Vec<PySortExpr>,
+}
+
+/// Arrow IPC writer for either the file or the stream format, buffering the
+/// encoded bytes in memory until they are taken
+enum IpcWriter {
+    File(FileWriter<Vec<u8>>),
+    Stream(StreamWriter<Vec<u8>>),
+}
+
+impl IpcWriter {
+    fn try_new(
+        schema: &Schema,
+        options: IpcWriteOptions,
+        stream_format: bool,
+    ) -> Result<Self, ArrowError> {
+        Ok(if stream_format {
+            Self::Stream(StreamWriter::try_new_with_options(
+                Vec::new(),
+                schema,
+                options,
+            )?)
+        } else {
+            Self::File(FileWriter::try_new_with_options(
+                Vec::new(),
+                schema,
+                options,
+            )?)
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), ArrowError> {
+        match self {
+            Self::File(writer) => writer.write(batch),
+            Self::Stream(writer) => writer.write(batch),
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), ArrowError> {
+        match self {
+            Self::File(writer) => writer.finish(),
+            Self::Stream(writer) => writer.finish(),
+        }
+    }
+
+    /// Take the bytes encoded so far
+    fn take_bytes(&mut self) -> Vec<u8> {
+        match self {
+            Self::File(writer) => std::mem::take(writer.get_mut()),
+            Self::Stream(writer) => std::mem::take(writer.get_mut()),
+        }
+    }
+}
+
+/// Execute `df` and write the results to a single Arrow IPC file at `path`,
+/// uploading the encoded batches as they are produced
+async fn write_ipc(
+    df: DataFrame,
+    path: &str,
+    options: IpcWriteOptions,
+    stream_format: bool,
+) -> datafusion::error::Result<()> {
+    let url = ListingTableUrl::parse(path)?;
+    let store = df
+        .task_ctx()
+        .runtime_env()
+        .object_store(url.object_store())?;
+    let mut stream = df.execute_stream().await?;
+
+    let mut output = BufWriter::new(store, url.prefix().clone());
+    let mut writer = IpcWriter::try_new(&stream.schema(), options, stream_format)?;
+    while let Some(batch) = stream.next().await {
+        writer.write(&batch?)?;
+        output.write_all(&writer.take_bytes()).await?;
+    }
+    writer.finish()?;
+    output.write_all(&writer.take_bytes()).await?;
+    output.shutdown().await?;
+    Ok(())
+}
+
+/// Files found at the output path of a write that overwrites it. They are only
+/// deleted after the write succeeded, so that a failing write keeps the existing
+/// data and the DataFrame can read the files it replaces.
+struct PreviousOutput {
+    store: Arc<dyn ObjectStore>,
+    files: Vec<ObjectPath>,
+}
+
+impl PreviousOutput {
+    /// List the files with `file_extension` currently stored at `path`, directly
+    /// or in Hive partition directories. Other files are left untouched.
+    async fn list(
+        df: &DataFrame,
+        path: &str,
+        file_extension: &str,
+    ) -> Result<Self, DataFusionError> {
+        let url = ListingTableUrl::parse(path)?;
+        if url.prefix().as_ref().is_empty() {
+            return Err(DataFusionError::Common(format!(
+                "Refusing to overwrite {path}, the root of its object store"
+            )));
+        }
+        let store = df
+            .task_ctx()
+            .runtime_env()
+            .object_store(url.object_store())?;
+        // A single file output replaces the file at `path` itself, which must
+        // not be deleted after the write
+        let files = store
+            .list(Some(url.prefix()))
+            .map_ok(|meta| meta.location)
+            .try_filter(|location| {
+                futures::future::ready(
+                    location != url.prefix()
+                        && is_output_file(url.prefix(), location, file_extension),
+                )
+            })
+            .try_collect()
+            .await
+            .map_err(datafusion::error::DataFusionError::from)?;
+        Ok(Self { store, files })
+    }
+
+    async fn delete(self) -> Result<(), DataFusionError> {
+        let locations = futures::stream::iter(self.files.into_iter().map(Ok)).boxed();
+        self.store
+            .delete_stream(locations)
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(datafusion::error::DataFusionError::from)?;
+        Ok(())
+    }
+}
+
+/// Whether `location` looks like a file written below `prefix` with
+/// `file_extension`, possibly followed by the extension of a compression, either
+/// directly or in Hive partition directories such as `year=2024/`
+fn is_output_file(prefix: &ObjectPath, location: &ObjectPath, file_extension: &str) -> bool {
+    let Some(parts) = location.prefix_match(prefix) else {
+        return false;
+    };
+    let parts: Vec<_> = parts.collect();
+    let Some((file, directories)) = parts.split_last() else {
+        return false;
+    };
+    let extension = format!(".{file_extension}");
+    directories.iter().all(|dir| dir.as_ref().contains('='))
+        && (file.as_ref().ends_with(&extension) || file.as_ref().contains(&format!("{extension}.")))
+}
+
+/// Delete the files replaced by a write, if it overwrote its output path
+fn delete_previous_output(py: Python, previous_output: Option<PreviousOutput>) -> PyResult<()> {
+    if let Some(previous_output) = previous_output {
+        wait_for_future(py, previous_output.delete())?;
+    }
+    Ok(())
+}
+
+/// Replace the contents of a registered table with the results of a DataFrame.
+///
+/// DataFusion does not support overwriting listing tables, so the results are
+/// appended as new files and the files listed before the write are deleted
+/// afterwards, which lets the DataFrame read the table it overwrites.
+async fn overwrite_table(df: DataFrame, table_name: &str) -> Result<(), DataFusionError> {
+    let (state, plan) = df.into_parts();
+    let catalog_options = &state.config_options().catalog;
+    let table_ref = TableReference::from(table_name).resolve(
+        &catalog_options.default_catalog,
+        &catalog_options.default_schema,
+    );
+    let schema = state
+        .catalog_list()
+        .catalog(&table_ref.catalog)
+        .and_then(|catalog| catalog.schema(&table_ref.schema));
+    let table = match schema {
+        Some(schema) => schema.table(&table_ref.table).await?,
+        None => None,
+    };
+    let Some(table) = table else {
+        return Err(DataFusionError::Common(format!(
+            "Table '{table_name}' not found"
+        )));
+    };
+
+    let Some(listing_table) = table.as_any().downcast_ref::<ListingTable>() else {
+        let write_options = DataFrameWriteOptions::new().with_insert_operation(InsertOp::Overwrite);
+        DataFrame::new(state, plan)
+            .write_table(table_name, write_options)
+            .await?;
+        return Ok(());
+    };
+
+    let mut previous_outputs = vec![];
+    for table_path in listing_table.table_paths() {
+        let store = state.runtime_env().object_store(table_path)?;
+        let files: Vec<_> = table_path
+            .list_all_files(
+                &state,
+                store.as_ref(),
+                &listing_table.options().file_extension,
+            )
+            .await?
+            .map_ok(|meta| meta.location)
+            .try_collect()
+            .await?;
+        previous_outputs.push(PreviousOutput { store, files });
+    }
+
+    DataFrame::new(state, plan)
+        .write_table(table_name, DataFrameWriteOptions::new())
+        .await?;
+
+    for previous_output in previous_outputs {
+        previous_output.delete().await?;
+    }
+    Ok(())
 }
 
 #[pymethods]
@@ -231,13 +566,41 @@ impl PyDataFrame {
     /// Executes the plan, returning a list of `RecordBatch`es.
     /// Unless some order is specified in the plan, there is no
     /// guarantee of the order of the result.
-    fn collect(&self, py: Python) -> PyResult<Vec<PyObject>> {
-        let batches = wait_for_future(py, self.df.as_ref().clone().collect())?;
+    #[pyo3(signature = (timeout=None, token=None))]
+    fn collect(
+        &self,
+        py: Python,
+        timeout: Option<f64>,
+        token: Option<PyCancellationToken>,
+    ) -> PyResult<Vec<PyObject>> {
+        let deadline = timeout_to_deadline(timeout)?;
+        let token = token.map(|t| t.token);
+        let batches = wait_for_future_cancellable(
+            py,
+            self.df.as_ref().clone().collect(),
+            token.as_ref(),
+            deadline,
+        )??;
         // cannot use PyResult<Vec<RecordBatch>> return type due to
         // https://github.com/PyO3/pyo3/issues/1813
         batches.into_iter().map(|rb| rb.to_pyarrow(py)).collect()
     }
 
+    /// Executes the plan without blocking, returning an awaitable that resolves
+    /// to a list of `RecordBatch`es.
+    fn collect_async<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let df = self.df.as_ref().clone();
+        future_into_py(py, async move {
+            let batches = df.collect().await.map_err(DataFusionError::from)?;
+            Python::with_gil(|py| {
+                batches
+                    .into_iter()
+                    .map(|rb| rb.to_pyarrow(py))
+                    .collect::<PyResult<Vec<_>>>()
+            })
+        })
+    }
+
     /// Cache DataFrame.
     fn cache(&self, py: Python) -> PyResult<Self> {
         let df = wait_for_future(py, self.df.as_ref().clone().cache())?;
@@ -246,8 +609,21 @@ impl PyDataFrame {
 
     /// Executes this DataFrame and collects all results into a vector of vector of RecordBatch
     /// maintaining the input partitioning.
-    fn collect_partitioned(&self, py: Python) -> PyResult<Vec<Vec<PyObject>>> {
-        let batches = wait_for_future(py, self.df.as_ref().clone().collect_partitioned())?;
+    #[pyo3(signature = (timeout=None, token=None))]
+    fn collect_partitioned(
+        &self,
+        py: Python,
+        timeout: Option<f64>,
+        token: Option<PyCancellationToken>,
+    ) -> PyResult<Vec<Vec<PyObject>>> {
+        let deadline = timeout_to_deadline(timeout)?;
+        let token = token.map(|t| t.token);
+        let batches = wait_for_future_cancellable(
+            py,
+            self.df.as_ref().clone().collect_partitioned(),
+            token.as_ref(),
+            deadline,
+        )??;
 
         batches
             .into_iter()
@@ -444,33 +820,79 @@ impl PyDataFrame {
     }
 
     /// Write a `DataFrame` to a CSV file.
-    fn write_csv(&self, path: &str, with_header: bool, py: Python) -> PyResult<()> {
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        path,
+        with_header,
+        write_options=None,
+        delimiter=",",
+        quote="\"",
+        escape=None,
+        double_quote=None,
+        terminator=None,
+        null_value=None,
+        date_format=None,
+        datetime_format=None,
+        timestamp_format=None,
+        timestamp_tz_format=None,
+        time_format=None,
+        file_compression_type=None))]
+    fn write_csv(
+        &self,
+        path: &str,
+        with_header: bool,
+        write_options: Option<PyWriteOptions>,
+        delimiter: &str,
+        quote: &str,
+        escape: Option<&str>,
+        double_quote: Option<bool>,
+        terminator: Option<&str>,
+        null_value: Option<String>,
+        date_format: Option<String>,
+        datetime_format: Option<String>,
+        timestamp_format: Option<String>,
+        timestamp_tz_format: Option<String>,
+        time_format: Option<String>,
+        file_compression_type: Option<String>,
+        py: Python,
+    ) -> PyResult<()> {
         let csv_options = CsvOptions {
             has_header: Some(with_header),
+            delimiter: single_byte("Delimiter", delimiter)?,
+            quote: single_byte("Quote", quote)?,
+            escape: escape.map(|e| single_byte("Escape", e)).transpose()?,
+            double_quote,
+            terminator: terminator
+                .map(|t| single_byte("Terminator", t))
+                .transpose()?,
+            null_value,
+            date_format,
+            datetime_format,
+            timestamp_format,
+            timestamp_tz_format,
+            time_format,
+            compression: *parse_file_compression_type(file_compression_type)?.get_variant(),
             ..Default::default()
         };
-        wait_for_future(
-            py,
-            self.df.as_ref().clone().write_csv(
-                path,
-                DataFrameWriteOptions::new(),
-                Some(csv_options),
-            ),
-        )?;
-        Ok(())
+        let (df, path, write_options, previous_output) =
+            self.prepare_write(path, write_options, "csv", py)?;
+        wait_for_future(py, df.write_csv(&path, write_options, Some(csv_options)))?;
+        delete_previous_output(py, previous_output)
     }
 
     /// Write a `DataFrame` to a Parquet file.
     #[pyo3(signature = (
         path,
         compression="uncompressed",
-        compression_level=None
+        compression_level=None,
+        write_options=None
         ))]
     fn write_parquet(
         &self,
         path: &str,
         compression: &str,
         compression_level: Option<u32>,
+        write_options: Option<PyWriteOptions>,
         py: Python,
     ) -> PyResult<()> {
         fn verify_compression_level(cl: Option<u32>) -> Result<u32, PyErr> {
@@ -510,33 +932,131 @@ impl PyDataFrame {
         let mut options = TableParquetOptions::default();
         options.global.compression = Some(compression_string);
 
+        let (df, path, write_options, previous_output) =
+            self.prepare_write(path, write_options, "parquet", py)?;
         wait_for_future(
             py,
-            self.df.as_ref().clone().write_parquet(
-                path,
-                DataFrameWriteOptions::new(),
-                Option::from(options),
-            ),
+            df.write_parquet(&path, write_options, Option::from(options)),
         )?;
-        Ok(())
+        delete_previous_output(py, previous_output)
     }
 
-    /// Executes a query and writes the results to a partitioned JSON file.
-    fn write_json(&self, path: &str, py: Python) -> PyResult<()> {
+    /// Write a `DataFrame` to a parquet file using writer options given as
+    /// `(key, value)` pairs. Keys follow the `format.*` options of `COPY TO`,
+    /// e.g. `max_row_group_size`, `bloom_filter_enabled::col` for a single
+    /// column or `metadata::key` for key-value metadata.
+    #[pyo3(signature = (path, options, write_options=None))]
+    fn write_parquet_with_options(
+        &self,
+        path: &str,
+        options: HashMap<String, String>,
+        write_options: Option<PyWriteOptions>,
+        py: Python,
+    ) -> PyResult<()> {
+        let mut parquet_options = TableParquetOptions::default();
+        for (key, value) in options {
+            parquet_options
+                .set(&key, &value)
+                .map_err(DataFusionError::from)?;
+        }
+
+        let (df, path, write_options, previous_output) =
+            self.prepare_write(path, write_options, "parquet", py)?;
         wait_for_future(
             py,
-            self.df
-                .as_ref()
-                .clone()
-                .write_json(path, DataFrameWriteOptions::new(), None),
+            df.write_parquet(&path, write_options, Some(parquet_options)),
         )?;
+        delete_previous_output(py, previous_output)
+    }
+
+    /// Write a `DataFrame` to a single Arrow IPC file, in either the IPC file
+    /// (Feather V2) or the IPC stream format
+    #[pyo3(signature = (path, format="file", compression=None, write_options=None))]
+    fn write_arrow(
+        &self,
+        path: &str,
+        format: &str,
+        compression: Option<&str>,
+        write_options: Option<PyWriteOptions>,
+        py: Python,
+    ) -> PyResult<()> {
+        let stream_format = match format.to_lowercase().as_str() {
+            "file" => false,
+            "stream" => true,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unrecognized Arrow IPC format {format}, expected file or stream"
+                )));
+            }
+        };
+        let compression = match compression.map(|c| c.to_lowercase()).as_deref() {
+            None | Some("uncompressed") => None,
+            Some("lz4") => Some(CompressionType::LZ4_FRAME),
+            Some("zstd") => Some(CompressionType::ZSTD),
+            Some(_) => {
+                return Err(PyValueError::new_err(format!(
+                    "Unrecognized compression type {}",
+                    compression.unwrap_or_default()
+                )));
+            }
+        };
+        if let Some(options) = &write_options {
+            if !options.partition_by.is_empty() || options.single_file_output == Some(false) {
+                return Err(PyValueError::new_err(
+                    "write_arrow only supports writing a single file",
+                ));
+            }
+        }
+        let ipc_options = IpcWriteOptions::default()
+            .try_with_compression(compression)
+            .map_err(DataFusionError::from)?;
+
+        let (df, path, _, previous_output) =
+            self.prepare_write(path, write_options, "arrow", py)?;
+        wait_for_future(py, write_ipc(df, &path, ipc_options, stream_format))
+            .map_err(DataFusionError::from)?;
+        delete_previous_output(py, previous_output)
+    }
+
+    /// Executes a query and writes the results to a partitioned JSON file.
+    #[pyo3(signature = (path, write_options=None, file_compression_type=None, date_format=None, timestamp_format=None))]
+    fn write_json(
+        &self,
+        path: &str,
+        write_options: Option<PyWriteOptions>,
+        file_compression_type: Option<String>,
+        date_format: Option<&str>,
+        timestamp_format: Option<&str>,
+        py: Python,
+    ) -> PyResult<()> {
+        let json_options = JsonOptions {
+            compression: *parse_file_compression_type(file_compression_type)?.get_variant(),
+            ..Default::default()
+        };
+        let (df, path, write_options, previous_output) =
+            self.prepare_write(path, write_options, "json", py)?;
+        let df = format_temporal_columns(df, date_format, timestamp_format)?;
+        wait_for_future(py, df.write_json(&path, write_options, Some(json_options)))?;
+        delete_previous_output(py, previous_output)
+    }
+
+    /// Executes a query and inserts the results into a registered table, which must
+    /// support inserts, such as a listing table or a memory table.
+    #[pyo3(signature = (table_name, overwrite=false))]
+    fn write_table(&self, table_name: &str, overwrite: bool, py: Python) -> PyResult<()> {
+        let df = self.df.as_ref().clone();
+        if overwrite {
+            wait_for_future(py, overwrite_table(df, table_name))?;
+        } else {
+            wait_for_future(py, df.write_table(table_name, DataFrameWriteOptions::new()))?;
+        }
         Ok(())
     }
 
     /// Convert to Arrow Table
     /// Collect the batches and pass to Arrow Table
     fn to_arrow_table(&self, py: Python<'_>) -> PyResult<PyObject> {
-        let batches = self.collect(py)?.to_object(py);
+        let batches = self.collect(py, None, None)?.to_object(py);
         let schema: PyObject = self.schema().into_py(py);
 
         // Instantiate pyarrow Table object and use its from_batches method
@@ -580,26 +1100,65 @@ impl PyDataFrame {
         PyCapsule::new_bound(py, ffi_stream, Some(stream_capsule_name))
     }
 
-    fn execute_stream(&self, py: Python) -> PyResult<PyRecordBatchStream> {
+    /// Executes the plan, returning a stream over a single partition. The
+    /// `timeout` bounds the whole execution including reading from the stream.
+    #[pyo3(signature = (timeout=None, token=None))]
+    fn execute_stream(
+        &self,
+        py: Python,
+        timeout: Option<f64>,
+        token: Option<PyCancellationToken>,
+    ) -> PyResult<PyRecordBatchStream> {
+        let deadline = timeout_to_deadline(timeout)?;
+        let token = token.map(|t| t.token);
         // create a Tokio runtime to run the async code
         let rt = &get_tokio_runtime().0;
         let df = self.df.as_ref().clone();
-        let fut: JoinHandle<datafusion::common::Result<SendableRecordBatchStream>> =
+        let mut handle: JoinHandle<datafusion::common::Result<SendableRecordBatchStream>> =
             rt.spawn(async move { df.execute_stream().await });
-        let stream = wait_for_future(py, fut).map_err(py_datafusion_err)?;
-        Ok(PyRecordBatchStream::new(stream?))
+        let result = wait_for_future_cancellable(py, &mut handle, token.as_ref(), deadline);
+        if result.is_err() {
+            // dropping the handle would leave the task running
+            handle.abort();
+        }
+        let stream = result?.map_err(py_datafusion_err)?;
+        Ok(PyRecordBatchStream::new(stream?).with_cancellation(token, deadline))
+    }
+
+    fn execute_stream_async<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let df = self.df.as_ref().clone();
+        future_into_py(py, async move {
+            let stream = df.execute_stream().await.map_err(DataFusionError::from)?;
+            Ok(PyRecordBatchStream::new(stream))
+        })
     }
 
-    fn execute_stream_partitioned(&self, py: Python) -> PyResult<Vec<PyRecordBatchStream>> {
+    #[pyo3(signature = (timeout=None, token=None))]
+    fn execute_stream_partitioned(
+        &self,
+        py: Python,
+        timeout: Option<f64>,
+        token: Option<PyCancellationToken>,
+    ) -> PyResult<Vec<PyRecordBatchStream>> {
+        let deadline = timeout_to_deadline(timeout)?;
+        let token = token.map(|t| t.token);
         // create a Tokio runtime to run the async code
         let rt = &get_tokio_runtime().0;
         let df = self.df.as_ref().clone();
-        let fut: JoinHandle<datafusion::common::Result<Vec<SendableRecordBatchStream>>> =
+        let mut handle: JoinHandle<datafusion::common::Result<Vec<SendableRecordBatchStream>>> =
             rt.spawn(async move { df.execute_stream_partitioned().await });
-        let stream = wait_for_future(py, fut).map_err(py_datafusion_err)?;
+        let result = wait_for_future_cancellable(py, &mut handle, token.as_ref(), deadline);
+        if result.is_err() {
+            // dropping the handle would leave the task running
+            handle.abort();
+        }
+        let stream = result?.map_err(py_datafusion_err)?;
 
         match stream {
-            Ok(batches) => Ok(batches.into_iter().map(PyRecordBatchStream::new).collect()),
+            Ok(batches) => Ok(batches
+                .into_iter()
+                .map(|s| PyRecordBatchStream::new(s).with_cancellation(token.clone(), deadline))
+                .collect()),
             _ => Err(PyValueError::new_err(
                 "Unable to execute stream partitioned",
             )),
@@ -648,14 +1207,15 @@ impl PyDataFrame {
 
     // Executes this DataFrame to get the total number of rows.
     fn count(&self, py: Python) -> PyResult<usize> {
-        Ok(wait_for_future(py, self.df.as_ref().clone().count())?)
+        let count = wait_for_future_cancellable(py, self.df.as_ref().clone().count(), None, None)??;
+        Ok(count)
     }
 }
 
 /// Print DataFrame
 fn print_dataframe(py: Python, df: DataFrame) -> PyResult<()> {
     // Get string representation of record batches
-    let batches = wait_for_future(py, df.collect())?;
+    let batches = wait_for_future_cancellable(py, df.collect(), None, None)??;
     let batches_as_string = pretty::pretty_format_batches(&batches);
     let result = match batches_as_string {
         Ok(batch) => format!("DataFrame()\n{batch}"),
@@ -725,7 +1285,7 @@ fn record_batch_into_schema(
     RecordBatch::try_new(schema, data_arrays)
 }
 
-fn validate_pycapsule(capsule: &Bound<PyCapsule>, name: &str) -> PyResult<()> {
+pub(crate) fn validate_pycapsule(capsule: &Bound<PyCapsule>, name: &str) -> PyResult<()> {
     let capsule_name = capsule.name()?;
     if capsule_name.is_none() {
         return Err(PyValueError::new_err(
@@ -44,8 +44,8 @@ use datafusion::physical_plan::{
 use crate::errors::DataFusionError;
 use crate::pyarrow_filter_expression::PyArrowFilterExpression;
 
-struct PyArrowBatchesAdapter {
-    batches: Py<PyIterator>,
+pub(crate) struct PyArrowBatchesAdapter {
+    pub(crate) batches: Py<PyIterator>,
 }
 
 impl Iterator for PyArrowBatchesAdapter {
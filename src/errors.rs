@@ -22,10 +22,17 @@ use std::fmt::Debug;
 use datafusion::arrow::error::ArrowError;
 use datafusion::error::DataFusionError as InnerDataFusionError;
 use prost::EncodeError;
-use pyo3::{exceptions::PyException, PyErr};
+use pyo3::{create_exception, exceptions::PyException, PyErr};
 
 pub type Result<T> = std::result::Result<T, DataFusionError>;
 
+create_exception!(
+    datafusion,
+    QueryCancelledError,
+    PyException,
+    "Raised when a query is cancelled or exceeds its timeout."
+);
+
 #[derive(Debug)]
 pub enum DataFusionError {
     ExecutionError(InnerDataFusionError),
@@ -0,0 +1,366 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Stable C ABIs for functions and tables compiled into separate shared libraries.
+//!
+//! Rust crates that want to provide functions or tables to datafusion-python
+//! without linking against the same build of DataFusion wrap them in one of
+//! the following structs and return it from a python method inside a PyCapsule:
+//!
+//! | Kind           | Struct                | Method                          | Capsule name                    |
+//! |----------------|-----------------------|---------------------------------|---------------------------------|
+//! | Scalar UDF     | [`FFI_ScalarUDF`]     | `__datafusion_scalar_udf__`     | [`SCALAR_UDF_CAPSULE_NAME`]     |
+//! | Aggregate UDF  | [`FFI_AggregateUDF`]  | `__datafusion_aggregate_udf__`  | [`AGGREGATE_UDF_CAPSULE_NAME`]  |
+//! | Window UDF     | [`FFI_WindowUDF`]     | `__datafusion_window_udf__`     | [`WINDOW_UDF_CAPSULE_NAME`]     |
+//! | Table provider | [`FFI_TableProvider`] | `__datafusion_table_provider__` | [`TABLE_PROVIDER_CAPSULE_NAME`] |
+//!
+//! Table providers use the interface of the `datafusion-ffi` crate. All other
+//! data crosses the boundary through the Arrow C data interface, so both sides
+//! only need to agree on the layout of the structs defined here, independently
+//! of the DataFusion and Arrow versions they are built with. Every struct defined
+//! here starts with the [`FFI_ABI_VERSION`] it was built for and its size, which
+//! are checked before any other field is used so that a plugin built for another
+//! layout is rejected with an error rather than crashing the process.
+//!
+//! Example usage in a plugin crate built with pyo3:
+//!
+//! ```ignore
+//! #[pymethods]
+//! impl MyFunction {
+//!     fn __datafusion_scalar_udf__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+//!         let udf = FFI_ScalarUDF::from(Arc::new(ScalarUDF::from(MyFunctionImpl::new())));
+//!         let name = CString::new(SCALAR_UDF_CAPSULE_NAME).unwrap();
+//!         PyCapsule::new_bound(py, udf, Some(name))
+//!     }
+//! }
+//! ```
+
+use std::ffi::{c_char, CStr, CString};
+use std::fmt::Display;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{make_array, Array, ArrayRef, StructArray};
+use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema};
+use datafusion::arrow::ffi::{from_ffi, to_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use datafusion::common::ScalarValue;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::{Signature, TypeSignature, Volatility};
+use datafusion::physical_expr::expressions::{Column, Literal};
+use datafusion::physical_plan::PhysicalExpr;
+
+pub mod aggregate_udf;
+pub mod scalar_udf;
+pub mod window_udf;
+
+pub use aggregate_udf::{FFI_Accumulator, FFI_AggregateUDF, ForeignAggregateUDF};
+pub use datafusion_ffi::table_provider::{FFI_TableProvider, ForeignTableProvider};
+pub use scalar_udf::{FFI_ScalarUDF, ForeignScalarUDF};
+pub use window_udf::{FFI_PartitionEvaluator, FFI_WindowUDF, ForeignWindowUDF};
+
+/// Version of the layout of the structs defined in this module, increased
+/// whenever one of them changes
+pub const FFI_ABI_VERSION: u32 = 1;
+
+/// Name of the PyCapsule holding a [`FFI_ScalarUDF`]
+pub const SCALAR_UDF_CAPSULE_NAME: &str = "datafusion_scalar_udf";
+
+/// Name of the PyCapsule holding a [`FFI_AggregateUDF`]
+pub const AGGREGATE_UDF_CAPSULE_NAME: &str = "datafusion_aggregate_udf";
+
+/// Name of the PyCapsule holding a [`FFI_WindowUDF`]
+pub const WINDOW_UDF_CAPSULE_NAME: &str = "datafusion_window_udf";
+
+/// Name of the PyCapsule holding a [`FFI_TableProvider`]
+pub const TABLE_PROVIDER_CAPSULE_NAME: &str = "datafusion_table_provider";
+
+/// Signature of the functions computing data types from the argument types
+type DataTypesFn<T> =
+    unsafe extern "C" fn(&T, *const FFI_ArrowSchema, usize, *mut FFI_ArrowSchema) -> *mut c_char;
+
+fn error_to_c(error: impl Display) -> *mut c_char {
+    let message = error.to_string().replace('\0', " ");
+    CString::new(message).unwrap_or_default().into_raw()
+}
+
+/// Run `f`, converting errors and panics into an error message since panics
+/// must not unwind across the C ABI
+fn catch_error(f: impl FnOnce() -> Result<()>) -> *mut c_char {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => std::ptr::null_mut(),
+        Ok(Err(e)) => error_to_c(e),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            error_to_c(format!("Panic in foreign function: {message}"))
+        }
+    }
+}
+
+unsafe extern "C" fn release_error_fn_wrapper(error: *mut c_char) {
+    if !error.is_null() {
+        drop(CString::from_raw(error));
+    }
+}
+
+/// Convert an error message returned by a foreign library into a result,
+/// releasing the message with the library's `release_error`
+fn check_error(error: *mut c_char, release_error: unsafe extern "C" fn(*mut c_char)) -> Result<()> {
+    if error.is_null() {
+        return Ok(());
+    }
+    let message = unsafe { CStr::from_ptr(error) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { release_error(error) };
+    Err(DataFusionError::Execution(message))
+}
+
+/// Check the `version` and `size` a struct received from a foreign library
+/// starts with against the layout of `T` this library was built with
+fn check_abi<T>(version: u32, size: usize) -> Result<()> {
+    let name = std::any::type_name::<T>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default();
+    if version != FFI_ABI_VERSION || size != std::mem::size_of::<T>() {
+        return Err(DataFusionError::Plan(format!(
+            "{name} was built for ABI version {version} with a size of {size} bytes, \
+             expected ABI version {FFI_ABI_VERSION} with a size of {} bytes. \
+             Rebuild the library providing it against this version of datafusion-python",
+            std::mem::size_of::<T>()
+        )));
+    }
+    Ok(())
+}
+
+fn name_to_c(name: &str) -> CString {
+    CString::new(name.replace('\0', " ")).unwrap_or_default()
+}
+
+unsafe fn name_from_c(name: *const c_char) -> String {
+    CStr::from_ptr(name).to_string_lossy().into_owned()
+}
+
+fn volatility_to_c(volatility: Volatility) -> u8 {
+    match volatility {
+        Volatility::Immutable => 0,
+        Volatility::Stable => 1,
+        Volatility::Volatile => 2,
+    }
+}
+
+fn volatility_from_c(volatility: u8, name: &str) -> Result<Volatility> {
+    match volatility {
+        0 => Ok(Volatility::Immutable),
+        1 => Ok(Volatility::Stable),
+        2 => Ok(Volatility::Volatile),
+        v => Err(DataFusionError::Execution(format!(
+            "Invalid volatility {v} for foreign function {name}"
+        ))),
+    }
+}
+
+unsafe fn data_types_from_c(
+    types: *const FFI_ArrowSchema,
+    num_types: usize,
+) -> Result<Vec<DataType>> {
+    if num_types == 0 {
+        return Ok(vec![]);
+    }
+    std::slice::from_raw_parts(types, num_types)
+        .iter()
+        .map(|t| DataType::try_from(t).map_err(DataFusionError::from))
+        .collect()
+}
+
+fn data_types_to_c(types: &[DataType]) -> Result<Vec<FFI_ArrowSchema>> {
+    types
+        .iter()
+        .map(|t| FFI_ArrowSchema::try_from(t).map_err(DataFusionError::from))
+        .collect()
+}
+
+/// Call a foreign function computing `num_out` data types from `arg_types`
+fn call_with_data_types<T>(
+    udf: &T,
+    release_error: unsafe extern "C" fn(*mut c_char),
+    arg_types: &[DataType],
+    num_out: usize,
+    f: DataTypesFn<T>,
+) -> Result<Vec<DataType>> {
+    let arg_types = data_types_to_c(arg_types)?;
+    let mut out = (0..num_out)
+        .map(|_| FFI_ArrowSchema::empty())
+        .collect::<Vec<_>>();
+    let error = unsafe { f(udf, arg_types.as_ptr(), arg_types.len(), out.as_mut_ptr()) };
+    check_error(error, release_error)?;
+    out.iter()
+        .map(|t| DataType::try_from(t).map_err(DataFusionError::from))
+        .collect()
+}
+
+/// Signature of a foreign function, whose arguments are coerced by the foreign
+/// library. DataFusion checks calls without arguments against the signature
+/// only, so they are accepted if the foreign function coerces an empty list of
+/// argument types.
+fn foreign_signature<T>(
+    udf: &T,
+    release_error: unsafe extern "C" fn(*mut c_char),
+    coerce_types: DataTypesFn<T>,
+    volatility: Volatility,
+) -> Signature {
+    if call_with_data_types(udf, release_error, &[], 0, coerce_types).is_ok() {
+        Signature::one_of(
+            vec![TypeSignature::Any(0), TypeSignature::UserDefined],
+            volatility,
+        )
+    } else {
+        Signature::user_defined(volatility)
+    }
+}
+
+/// Write the coerced argument types computed by `coerce` into `out`
+unsafe fn write_coerced_types(
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    out: *mut FFI_ArrowSchema,
+    coerce: impl FnOnce(&[DataType]) -> Result<Vec<DataType>>,
+) -> Result<()> {
+    let arg_types = data_types_from_c(arg_types, num_args)?;
+    let coerced = coerce(&arg_types)?;
+    if coerced.len() != num_args {
+        return Err(DataFusionError::Execution(format!(
+            "Expected {num_args} coerced types. Received {}",
+            coerced.len()
+        )));
+    }
+    for (i, t) in data_types_to_c(&coerced)?.into_iter().enumerate() {
+        out.add(i).write(t);
+    }
+    Ok(())
+}
+
+/// Pack `arrays` of `num_rows` rows into a single struct array exported through
+/// the C data interface
+fn arrays_to_c(arrays: &[ArrayRef], num_rows: usize) -> Result<(FFI_ArrowArray, FFI_ArrowSchema)> {
+    let array = if arrays.is_empty() {
+        StructArray::new_empty_fields(num_rows, None)
+    } else {
+        let fields: Fields = arrays
+            .iter()
+            .enumerate()
+            .map(|(i, a)| Field::new(i.to_string(), a.data_type().clone(), true))
+            .collect();
+        StructArray::try_new(fields, arrays.to_vec(), None)?
+    };
+    Ok(to_ffi(&array.to_data())?)
+}
+
+/// Import the columns of a struct array packed by [`arrays_to_c`], taking
+/// ownership of `array`
+unsafe fn arrays_from_c(
+    array: *mut FFI_ArrowArray,
+    schema: *const FFI_ArrowSchema,
+) -> Result<Vec<ArrayRef>> {
+    let array = std::ptr::replace(array, FFI_ArrowArray::empty());
+    let data = from_ffi(array, &*schema)?;
+    let array = make_array(data);
+    let array = array
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .ok_or_else(|| DataFusionError::Execution("Expected a struct array".to_string()))?;
+    Ok(array.columns().to_vec())
+}
+
+fn scalars_to_c(scalars: &[ScalarValue]) -> Result<(FFI_ArrowArray, FFI_ArrowSchema)> {
+    let arrays = scalars
+        .iter()
+        .map(|s| s.to_array())
+        .collect::<Result<Vec<_>>>()?;
+    arrays_to_c(&arrays, 1)
+}
+
+unsafe fn scalars_from_c(
+    array: *mut FFI_ArrowArray,
+    schema: *const FFI_ArrowSchema,
+) -> Result<Vec<ScalarValue>> {
+    arrays_from_c(array, schema)?
+        .iter()
+        .map(|a| ScalarValue::try_from_array(a, 0))
+        .collect()
+}
+
+/// Export the arguments of a foreign function call as a single row struct array
+/// with one column per argument, holding the value of literal arguments and
+/// null otherwise, along with whether each argument is a literal.
+///
+/// The argument expressions themselves are not passed over the C ABI, but some
+/// functions, such as `lag`, read options from literal arguments.
+fn arguments_to_c(
+    exprs: &[Arc<dyn PhysicalExpr>],
+    schema: &Schema,
+) -> Result<(FFI_ArrowArray, FFI_ArrowSchema, Vec<bool>)> {
+    let mut values = Vec::with_capacity(exprs.len());
+    let mut literal_args = Vec::with_capacity(exprs.len());
+    for expr in exprs {
+        match expr.as_any().downcast_ref::<Literal>() {
+            Some(literal) => {
+                values.push(literal.value().clone());
+                literal_args.push(true);
+            }
+            None => {
+                values.push(ScalarValue::try_from(expr.data_type(schema)?)?);
+                literal_args.push(false);
+            }
+        }
+    }
+    let (array, schema) = scalars_to_c(&values)?;
+    Ok((array, schema, literal_args))
+}
+
+/// Import the arguments exported by [`arguments_to_c`], returning their types
+/// and expressions over a schema of the argument types: literals for literal
+/// arguments and columns otherwise
+unsafe fn arguments_from_c(
+    args: *mut FFI_ArrowArray,
+    args_schema: *const FFI_ArrowSchema,
+    literal_args: *const bool,
+) -> Result<(Schema, Vec<Arc<dyn PhysicalExpr>>)> {
+    let values = arrays_from_c(args, args_schema)?;
+    let fields: Vec<_> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| Field::new(i.to_string(), v.data_type().clone(), true))
+        .collect();
+    let exprs = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            Ok(if *literal_args.add(i) {
+                Arc::new(Literal::new(ScalarValue::try_from_array(value, 0)?))
+                    as Arc<dyn PhysicalExpr>
+            } else {
+                Arc::new(Column::new(&i.to_string(), i)) as Arc<dyn PhysicalExpr>
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((Schema::new(fields), exprs))
+}
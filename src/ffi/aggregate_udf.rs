@@ -0,0 +1,660 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! C ABI for aggregate UDFs, see the [parent module](super) for how it is used.
+//!
+//! Only the [`Accumulator`] of a function is exposed. The ordering of an
+//! aggregate (`ORDER BY` within the call) is not passed to the foreign library.
+
+use std::any::Any;
+use std::ffi::{c_char, c_void, CString};
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::arrow::ffi::{FFI_ArrowArray, FFI_ArrowSchema};
+use datafusion::common::ScalarValue;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion::logical_expr::type_coercion::functions::data_types_with_aggregate_udf;
+use datafusion::logical_expr::{Accumulator, AggregateUDF, AggregateUDFImpl, Signature};
+
+use super::{
+    arguments_from_c, arguments_to_c, arrays_from_c, arrays_to_c, call_with_data_types,
+    catch_error, check_abi, check_error, data_types_from_c, data_types_to_c, foreign_signature,
+    name_from_c, name_to_c, release_error_fn_wrapper, scalars_from_c, scalars_to_c,
+    volatility_from_c, volatility_to_c, write_coerced_types, FFI_ABI_VERSION,
+};
+
+/// C ABI representation of an aggregate UDF.
+///
+/// Functions returning `*mut c_char` return a null pointer on success and an
+/// error message otherwise. Error messages must be freed with `release_error`.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct FFI_AggregateUDF {
+    /// [`FFI_ABI_VERSION`](super::FFI_ABI_VERSION) the struct was built for
+    pub version: u32,
+
+    /// Size of this struct in bytes
+    pub struct_size: usize,
+
+    /// Null terminated name of the function, valid for the lifetime of the struct
+    pub name: unsafe extern "C" fn(udf: &Self) -> *const c_char,
+
+    /// 0 for immutable, 1 for stable and 2 for volatile functions
+    pub volatility: unsafe extern "C" fn(udf: &Self) -> u8,
+
+    /// Coerce `num_args` argument types into the types accepted by the function,
+    /// writing one type for every argument into `out`
+    pub coerce_types: unsafe extern "C" fn(
+        udf: &Self,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        out: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Compute the return type for the (coerced) argument types
+    pub return_type: unsafe extern "C" fn(
+        udf: &Self,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        out: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Compute the fields of the accumulator state, written into `out` as a
+    /// struct data type
+    pub state_fields: unsafe extern "C" fn(
+        udf: &Self,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        return_type: &FFI_ArrowSchema,
+        is_distinct: bool,
+        out: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Create an accumulator for the (coerced) arguments, given as a single row
+    /// struct array with one column per argument that holds the value of the
+    /// arguments flagged as literals in `literal_args`. With `sliding`, the
+    /// accumulator must support `retract_batch` to evaluate bounded window frames
+    pub accumulator: unsafe extern "C" fn(
+        udf: &Self,
+        args: *mut FFI_ArrowArray,
+        args_schema: *const FFI_ArrowSchema,
+        literal_args: *const bool,
+        return_type: &FFI_ArrowSchema,
+        ignore_nulls: bool,
+        is_distinct: bool,
+        sliding: bool,
+        out: *mut FFI_Accumulator,
+    ) -> *mut c_char,
+
+    /// Free an error message returned by one of the functions above
+    pub release_error: unsafe extern "C" fn(error: *mut c_char),
+
+    /// Create an independent copy of the function
+    pub clone: unsafe extern "C" fn(udf: &Self) -> Self,
+
+    /// Release the resources held by the function
+    pub release: unsafe extern "C" fn(udf: &mut Self),
+
+    /// Data private to the library providing the function
+    pub private_data: *mut c_void,
+}
+
+unsafe impl Send for FFI_AggregateUDF {}
+unsafe impl Sync for FFI_AggregateUDF {}
+
+/// C ABI representation of an [`Accumulator`].
+///
+/// Batches of values and states are passed as a single struct array with one
+/// column per argument or state field, whose ownership is moved to the callee.
+/// Scalar results are written as a struct array with a single row.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct FFI_Accumulator {
+    /// [`FFI_ABI_VERSION`](super::FFI_ABI_VERSION) the struct was built for
+    pub version: u32,
+
+    /// Size of this struct in bytes
+    pub struct_size: usize,
+
+    /// Update the accumulator with a batch of argument values
+    pub update_batch: unsafe extern "C" fn(
+        accumulator: &mut Self,
+        values: *mut FFI_ArrowArray,
+        values_schema: *const FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Merge a batch of states of other accumulators into the accumulator
+    pub merge_batch: unsafe extern "C" fn(
+        accumulator: &mut Self,
+        states: *mut FFI_ArrowArray,
+        states_schema: *const FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Remove a batch of argument values from the accumulator, only called when
+    /// `supports_retract_batch` returns true
+    pub retract_batch: unsafe extern "C" fn(
+        accumulator: &mut Self,
+        values: *mut FFI_ArrowArray,
+        values_schema: *const FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Whether `retract_batch` is implemented
+    pub supports_retract_batch: unsafe extern "C" fn(accumulator: &Self) -> bool,
+
+    /// Compute the final value, written as a single row struct array with one
+    /// column
+    pub evaluate: unsafe extern "C" fn(
+        accumulator: &mut Self,
+        out_array: *mut FFI_ArrowArray,
+        out_schema: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Compute the intermediate state, written as a single row struct array
+    /// with one column per state field
+    pub state: unsafe extern "C" fn(
+        accumulator: &mut Self,
+        out_array: *mut FFI_ArrowArray,
+        out_schema: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Allocated size of the accumulator in bytes
+    pub size: unsafe extern "C" fn(accumulator: &Self) -> usize,
+
+    /// Free an error message returned by one of the functions above
+    pub release_error: unsafe extern "C" fn(error: *mut c_char),
+
+    /// Release the resources held by the accumulator
+    pub release: unsafe extern "C" fn(accumulator: &mut Self),
+
+    /// Data private to the library providing the accumulator
+    pub private_data: *mut c_void,
+}
+
+unsafe impl Send for FFI_Accumulator {}
+unsafe impl Sync for FFI_Accumulator {}
+
+struct AggregateUDFPrivateData {
+    udf: Arc<AggregateUDF>,
+    name: CString,
+}
+
+unsafe fn private_data(udf: &FFI_AggregateUDF) -> &AggregateUDFPrivateData {
+    &*(udf.private_data as *const AggregateUDFPrivateData)
+}
+
+unsafe extern "C" fn name_fn_wrapper(udf: &FFI_AggregateUDF) -> *const c_char {
+    private_data(udf).name.as_ptr()
+}
+
+unsafe extern "C" fn volatility_fn_wrapper(udf: &FFI_AggregateUDF) -> u8 {
+    volatility_to_c(private_data(udf).udf.signature().volatility)
+}
+
+unsafe extern "C" fn coerce_types_fn_wrapper(
+    udf: &FFI_AggregateUDF,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    out: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        write_coerced_types(arg_types, num_args, out, |arg_types| {
+            let udf = &private_data(udf).udf;
+            data_types_with_aggregate_udf(arg_types, udf)
+        })
+    })
+}
+
+unsafe extern "C" fn return_type_fn_wrapper(
+    udf: &FFI_AggregateUDF,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    out: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let arg_types = data_types_from_c(arg_types, num_args)?;
+        let return_type = private_data(udf).udf.inner().return_type(&arg_types)?;
+        out.write(FFI_ArrowSchema::try_from(&return_type)?);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn state_fields_fn_wrapper(
+    udf: &FFI_AggregateUDF,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    return_type: &FFI_ArrowSchema,
+    is_distinct: bool,
+    out: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let udf = &private_data(udf).udf;
+        let arg_types = data_types_from_c(arg_types, num_args)?;
+        let return_type = DataType::try_from(return_type)?;
+        let fields = udf.state_fields(StateFieldsArgs {
+            name: udf.name(),
+            input_types: &arg_types,
+            return_type: &return_type,
+            ordering_fields: &[],
+            is_distinct,
+        })?;
+        out.write(FFI_ArrowSchema::try_from(DataType::Struct(fields.into()))?);
+        Ok(())
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn accumulator_fn_wrapper(
+    udf: &FFI_AggregateUDF,
+    args: *mut FFI_ArrowArray,
+    args_schema: *const FFI_ArrowSchema,
+    literal_args: *const bool,
+    return_type: &FFI_ArrowSchema,
+    ignore_nulls: bool,
+    is_distinct: bool,
+    sliding: bool,
+    out: *mut FFI_Accumulator,
+) -> *mut c_char {
+    catch_error(|| {
+        let udf = &private_data(udf).udf;
+        let (schema, exprs) = arguments_from_c(args, args_schema, literal_args)?;
+        let return_type = DataType::try_from(return_type)?;
+        let acc_args = AccumulatorArgs {
+            return_type: &return_type,
+            schema: &schema,
+            ignore_nulls,
+            ordering_req: &[],
+            is_reversed: false,
+            name: udf.name(),
+            is_distinct,
+            exprs: &exprs,
+        };
+        let accumulator = if sliding {
+            udf.create_sliding_accumulator(acc_args)?
+        } else {
+            udf.accumulator(acc_args)?
+        };
+        out.write(FFI_Accumulator::from(accumulator));
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn clone_fn_wrapper(udf: &FFI_AggregateUDF) -> FFI_AggregateUDF {
+    FFI_AggregateUDF::from(Arc::clone(&private_data(udf).udf))
+}
+
+unsafe extern "C" fn release_fn_wrapper(udf: &mut FFI_AggregateUDF) {
+    if !udf.private_data.is_null() {
+        drop(Box::from_raw(
+            udf.private_data as *mut AggregateUDFPrivateData,
+        ));
+        udf.private_data = std::ptr::null_mut();
+    }
+}
+
+impl From<Arc<AggregateUDF>> for FFI_AggregateUDF {
+    fn from(udf: Arc<AggregateUDF>) -> Self {
+        let name = name_to_c(udf.name());
+        let private_data = Box::new(AggregateUDFPrivateData { udf, name });
+        Self {
+            version: FFI_ABI_VERSION,
+            struct_size: std::mem::size_of::<Self>(),
+            name: name_fn_wrapper,
+            volatility: volatility_fn_wrapper,
+            coerce_types: coerce_types_fn_wrapper,
+            return_type: return_type_fn_wrapper,
+            state_fields: state_fields_fn_wrapper,
+            accumulator: accumulator_fn_wrapper,
+            release_error: release_error_fn_wrapper,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            private_data: Box::into_raw(private_data) as *mut c_void,
+        }
+    }
+}
+
+impl Clone for FFI_AggregateUDF {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl Drop for FFI_AggregateUDF {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+unsafe fn local_accumulator(accumulator: &FFI_Accumulator) -> &dyn Accumulator {
+    (*(accumulator.private_data as *const Box<dyn Accumulator>)).as_ref()
+}
+
+unsafe fn local_accumulator_mut(accumulator: &mut FFI_Accumulator) -> &mut dyn Accumulator {
+    (*(accumulator.private_data as *mut Box<dyn Accumulator>)).as_mut()
+}
+
+unsafe extern "C" fn update_batch_fn_wrapper(
+    accumulator: &mut FFI_Accumulator,
+    values: *mut FFI_ArrowArray,
+    values_schema: *const FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let values = arrays_from_c(values, values_schema)?;
+        local_accumulator_mut(accumulator).update_batch(&values)
+    })
+}
+
+unsafe extern "C" fn merge_batch_fn_wrapper(
+    accumulator: &mut FFI_Accumulator,
+    states: *mut FFI_ArrowArray,
+    states_schema: *const FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let states = arrays_from_c(states, states_schema)?;
+        local_accumulator_mut(accumulator).merge_batch(&states)
+    })
+}
+
+unsafe extern "C" fn retract_batch_fn_wrapper(
+    accumulator: &mut FFI_Accumulator,
+    values: *mut FFI_ArrowArray,
+    values_schema: *const FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let values = arrays_from_c(values, values_schema)?;
+        local_accumulator_mut(accumulator).retract_batch(&values)
+    })
+}
+
+unsafe extern "C" fn supports_retract_batch_fn_wrapper(accumulator: &FFI_Accumulator) -> bool {
+    local_accumulator(accumulator).supports_retract_batch()
+}
+
+unsafe extern "C" fn evaluate_fn_wrapper(
+    accumulator: &mut FFI_Accumulator,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let value = local_accumulator_mut(accumulator).evaluate()?;
+        let (array, schema) = scalars_to_c(&[value])?;
+        out_array.write(array);
+        out_schema.write(schema);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn state_fn_wrapper(
+    accumulator: &mut FFI_Accumulator,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let state = local_accumulator_mut(accumulator).state()?;
+        let (array, schema) = scalars_to_c(&state)?;
+        out_array.write(array);
+        out_schema.write(schema);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn size_fn_wrapper(accumulator: &FFI_Accumulator) -> usize {
+    local_accumulator(accumulator).size()
+}
+
+unsafe extern "C" fn release_accumulator_fn_wrapper(accumulator: &mut FFI_Accumulator) {
+    if !accumulator.private_data.is_null() {
+        drop(Box::from_raw(
+            accumulator.private_data as *mut Box<dyn Accumulator>,
+        ));
+        accumulator.private_data = std::ptr::null_mut();
+    }
+}
+
+impl From<Box<dyn Accumulator>> for FFI_Accumulator {
+    fn from(accumulator: Box<dyn Accumulator>) -> Self {
+        Self {
+            version: FFI_ABI_VERSION,
+            struct_size: std::mem::size_of::<Self>(),
+            update_batch: update_batch_fn_wrapper,
+            merge_batch: merge_batch_fn_wrapper,
+            retract_batch: retract_batch_fn_wrapper,
+            supports_retract_batch: supports_retract_batch_fn_wrapper,
+            evaluate: evaluate_fn_wrapper,
+            state: state_fn_wrapper,
+            size: size_fn_wrapper,
+            release_error: release_error_fn_wrapper,
+            release: release_accumulator_fn_wrapper,
+            private_data: Box::into_raw(Box::new(accumulator)) as *mut c_void,
+        }
+    }
+}
+
+impl Drop for FFI_Accumulator {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+/// An accumulator provided by another library through a [`FFI_Accumulator`]
+#[derive(Debug)]
+struct ForeignAccumulator {
+    accumulator: FFI_Accumulator,
+}
+
+impl ForeignAccumulator {
+    fn check(&self, error: *mut c_char) -> Result<()> {
+        check_error(error, self.accumulator.release_error)
+    }
+
+    fn call_with_arrays(
+        &mut self,
+        arrays: &[ArrayRef],
+        f: unsafe extern "C" fn(
+            &mut FFI_Accumulator,
+            *mut FFI_ArrowArray,
+            *const FFI_ArrowSchema,
+        ) -> *mut c_char,
+    ) -> Result<()> {
+        let num_rows = arrays.first().map(|a| a.len()).unwrap_or_default();
+        let (mut array, schema) = arrays_to_c(arrays, num_rows)?;
+        let error = unsafe { f(&mut self.accumulator, &mut array, &schema) };
+        self.check(error)
+    }
+
+    fn call_with_scalars(
+        &mut self,
+        f: unsafe extern "C" fn(
+            &mut FFI_Accumulator,
+            *mut FFI_ArrowArray,
+            *mut FFI_ArrowSchema,
+        ) -> *mut c_char,
+    ) -> Result<Vec<ScalarValue>> {
+        let mut array = FFI_ArrowArray::empty();
+        let mut schema = FFI_ArrowSchema::empty();
+        let error = unsafe { f(&mut self.accumulator, &mut array, &mut schema) };
+        self.check(error)?;
+        unsafe { scalars_from_c(&mut array, &schema) }
+    }
+}
+
+impl Accumulator for ForeignAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.call_with_arrays(values, self.accumulator.update_batch)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.call_with_arrays(states, self.accumulator.merge_batch)
+    }
+
+    fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.call_with_arrays(values, self.accumulator.retract_batch)
+    }
+
+    fn supports_retract_batch(&self) -> bool {
+        unsafe { (self.accumulator.supports_retract_batch)(&self.accumulator) }
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let mut value = self.call_with_scalars(self.accumulator.evaluate)?;
+        if value.len() != 1 {
+            return Err(DataFusionError::Execution(format!(
+                "Expected a single value from foreign accumulator. Received {}",
+                value.len()
+            )));
+        }
+        Ok(value.remove(0))
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        self.call_with_scalars(self.accumulator.state)
+    }
+
+    fn size(&self) -> usize {
+        unsafe { (self.accumulator.size)(&self.accumulator) }
+    }
+}
+
+/// An aggregate UDF provided by another library through a [`FFI_AggregateUDF`]
+#[derive(Debug)]
+pub struct ForeignAggregateUDF {
+    name: String,
+    signature: Signature,
+    udf: FFI_AggregateUDF,
+}
+
+impl ForeignAggregateUDF {
+    fn check(&self, error: *mut c_char) -> Result<()> {
+        check_error(error, self.udf.release_error)
+    }
+
+    fn new_accumulator(
+        &self,
+        acc_args: AccumulatorArgs,
+        sliding: bool,
+    ) -> Result<Box<dyn Accumulator>> {
+        let (mut args, args_schema, literal_args) =
+            arguments_to_c(acc_args.exprs, acc_args.schema)?;
+        let return_type = FFI_ArrowSchema::try_from(acc_args.return_type)?;
+        let mut out = std::mem::MaybeUninit::<FFI_Accumulator>::uninit();
+        let error = unsafe {
+            (self.udf.accumulator)(
+                &self.udf,
+                &mut args,
+                &args_schema,
+                literal_args.as_ptr(),
+                &return_type,
+                acc_args.ignore_nulls,
+                acc_args.is_distinct,
+                sliding,
+                out.as_mut_ptr(),
+            )
+        };
+        self.check(error)?;
+        let accumulator = unsafe { out.assume_init() };
+        check_abi::<FFI_Accumulator>(accumulator.version, accumulator.struct_size)?;
+        Ok(Box::new(ForeignAccumulator { accumulator }))
+    }
+}
+
+impl TryFrom<&FFI_AggregateUDF> for ForeignAggregateUDF {
+    type Error = DataFusionError;
+
+    fn try_from(udf: &FFI_AggregateUDF) -> Result<Self> {
+        check_abi::<FFI_AggregateUDF>(udf.version, udf.struct_size)?;
+        let udf = udf.clone();
+        let name = unsafe { name_from_c((udf.name)(&udf)) };
+        let volatility = volatility_from_c(unsafe { (udf.volatility)(&udf) }, &name)?;
+        let signature = foreign_signature(&udf, udf.release_error, udf.coerce_types, volatility);
+        Ok(Self {
+            name,
+            signature,
+            udf,
+        })
+    }
+}
+
+impl AggregateUDFImpl for ForeignAggregateUDF {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let mut return_type = call_with_data_types(
+            &self.udf,
+            self.udf.release_error,
+            arg_types,
+            1,
+            self.udf.return_type,
+        )?;
+        Ok(return_type.remove(0))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        call_with_data_types(
+            &self.udf,
+            self.udf.release_error,
+            arg_types,
+            arg_types.len(),
+            self.udf.coerce_types,
+        )
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<Field>> {
+        let arg_types = data_types_to_c(args.input_types)?;
+        let return_type = FFI_ArrowSchema::try_from(args.return_type)?;
+        let mut out = FFI_ArrowSchema::empty();
+        let error = unsafe {
+            (self.udf.state_fields)(
+                &self.udf,
+                arg_types.as_ptr(),
+                arg_types.len(),
+                &return_type,
+                args.is_distinct,
+                &mut out,
+            )
+        };
+        self.check(error)?;
+        match DataType::try_from(&out)? {
+            DataType::Struct(fields) => Ok(fields.iter().map(|f| f.as_ref().clone()).collect()),
+            t => Err(DataFusionError::Execution(format!(
+                "Expected a struct of state fields from foreign function {}. Received {t}",
+                self.name
+            ))),
+        }
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        self.new_accumulator(acc_args, false)
+    }
+
+    fn create_sliding_accumulator(&self, args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        self.new_accumulator(args, true)
+    }
+}
@@ -0,0 +1,310 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! C ABI for scalar UDFs, see the [parent module](super) for how it is used.
+
+use std::any::Any;
+use std::ffi::{c_char, c_void, CString};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{make_array, Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::ffi::{from_ffi, to_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::type_coercion::functions::data_types_with_scalar_udf;
+use datafusion::logical_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature};
+
+use super::{
+    call_with_data_types, catch_error, check_abi, check_error, data_types_from_c,
+    foreign_signature, name_from_c, name_to_c, release_error_fn_wrapper, volatility_from_c,
+    volatility_to_c, write_coerced_types, FFI_ABI_VERSION,
+};
+
+/// C ABI representation of a scalar UDF.
+///
+/// Functions returning `*mut c_char` return a null pointer on success and an
+/// error message otherwise. Error messages must be freed with `release_error`.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct FFI_ScalarUDF {
+    /// [`FFI_ABI_VERSION`](super::FFI_ABI_VERSION) the struct was built for
+    pub version: u32,
+
+    /// Size of this struct in bytes
+    pub struct_size: usize,
+
+    /// Null terminated name of the function, valid for the lifetime of the struct
+    pub name: unsafe extern "C" fn(udf: &Self) -> *const c_char,
+
+    /// 0 for immutable, 1 for stable and 2 for volatile functions
+    pub volatility: unsafe extern "C" fn(udf: &Self) -> u8,
+
+    /// Coerce `num_args` argument types into the types accepted by the function,
+    /// writing one type for every argument into `out`
+    pub coerce_types: unsafe extern "C" fn(
+        udf: &Self,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        out: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Compute the return type for the (coerced) argument types
+    pub return_type: unsafe extern "C" fn(
+        udf: &Self,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        out: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Evaluate the function. Ownership of the `args` arrays is moved to the
+    /// callee, which leaves released arrays in their place
+    pub invoke: unsafe extern "C" fn(
+        udf: &Self,
+        args: *mut FFI_ArrowArray,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        num_rows: usize,
+        out_array: *mut FFI_ArrowArray,
+        out_schema: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Free an error message returned by one of the functions above
+    pub release_error: unsafe extern "C" fn(error: *mut c_char),
+
+    /// Create an independent copy of the function
+    pub clone: unsafe extern "C" fn(udf: &Self) -> Self,
+
+    /// Release the resources held by the function
+    pub release: unsafe extern "C" fn(udf: &mut Self),
+
+    /// Data private to the library providing the function
+    pub private_data: *mut c_void,
+}
+
+unsafe impl Send for FFI_ScalarUDF {}
+unsafe impl Sync for FFI_ScalarUDF {}
+
+struct ScalarUDFPrivateData {
+    udf: Arc<ScalarUDF>,
+    name: CString,
+}
+
+unsafe fn private_data(udf: &FFI_ScalarUDF) -> &ScalarUDFPrivateData {
+    &*(udf.private_data as *const ScalarUDFPrivateData)
+}
+
+unsafe extern "C" fn name_fn_wrapper(udf: &FFI_ScalarUDF) -> *const c_char {
+    private_data(udf).name.as_ptr()
+}
+
+unsafe extern "C" fn volatility_fn_wrapper(udf: &FFI_ScalarUDF) -> u8 {
+    volatility_to_c(private_data(udf).udf.signature().volatility)
+}
+
+unsafe extern "C" fn coerce_types_fn_wrapper(
+    udf: &FFI_ScalarUDF,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    out: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        write_coerced_types(arg_types, num_args, out, |arg_types| {
+            data_types_with_scalar_udf(arg_types, &private_data(udf).udf)
+        })
+    })
+}
+
+unsafe extern "C" fn return_type_fn_wrapper(
+    udf: &FFI_ScalarUDF,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    out: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let arg_types = data_types_from_c(arg_types, num_args)?;
+        let return_type = private_data(udf).udf.inner().return_type(&arg_types)?;
+        out.write(FFI_ArrowSchema::try_from(&return_type)?);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn invoke_fn_wrapper(
+    udf: &FFI_ScalarUDF,
+    args: *mut FFI_ArrowArray,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    num_rows: usize,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let args = (0..num_args)
+            .map(|i| {
+                let array = std::ptr::replace(args.add(i), FFI_ArrowArray::empty());
+                let data = from_ffi(array, &*arg_types.add(i))?;
+                Ok(ColumnarValue::Array(make_array(data)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let result = private_data(udf).udf.invoke_batch(&args, num_rows)?;
+        let (array, schema) = to_ffi(&result.into_array(num_rows)?.to_data())?;
+        out_array.write(array);
+        out_schema.write(schema);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn clone_fn_wrapper(udf: &FFI_ScalarUDF) -> FFI_ScalarUDF {
+    FFI_ScalarUDF::from(Arc::clone(&private_data(udf).udf))
+}
+
+unsafe extern "C" fn release_fn_wrapper(udf: &mut FFI_ScalarUDF) {
+    if !udf.private_data.is_null() {
+        drop(Box::from_raw(udf.private_data as *mut ScalarUDFPrivateData));
+        udf.private_data = std::ptr::null_mut();
+    }
+}
+
+impl From<Arc<ScalarUDF>> for FFI_ScalarUDF {
+    fn from(udf: Arc<ScalarUDF>) -> Self {
+        let name = name_to_c(udf.name());
+        let private_data = Box::new(ScalarUDFPrivateData { udf, name });
+        Self {
+            version: FFI_ABI_VERSION,
+            struct_size: std::mem::size_of::<Self>(),
+            name: name_fn_wrapper,
+            volatility: volatility_fn_wrapper,
+            coerce_types: coerce_types_fn_wrapper,
+            return_type: return_type_fn_wrapper,
+            invoke: invoke_fn_wrapper,
+            release_error: release_error_fn_wrapper,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            private_data: Box::into_raw(private_data) as *mut c_void,
+        }
+    }
+}
+
+impl Clone for FFI_ScalarUDF {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl Drop for FFI_ScalarUDF {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+/// A scalar UDF provided by another library through a [`FFI_ScalarUDF`]
+#[derive(Debug)]
+pub struct ForeignScalarUDF {
+    name: String,
+    signature: Signature,
+    udf: FFI_ScalarUDF,
+}
+
+impl ForeignScalarUDF {
+    fn check(&self, error: *mut c_char) -> Result<()> {
+        check_error(error, self.udf.release_error)
+    }
+}
+
+impl TryFrom<&FFI_ScalarUDF> for ForeignScalarUDF {
+    type Error = DataFusionError;
+
+    fn try_from(udf: &FFI_ScalarUDF) -> Result<Self> {
+        check_abi::<FFI_ScalarUDF>(udf.version, udf.struct_size)?;
+        let udf = udf.clone();
+        let name = unsafe { name_from_c((udf.name)(&udf)) };
+        let volatility = volatility_from_c(unsafe { (udf.volatility)(&udf) }, &name)?;
+        let signature = foreign_signature(&udf, udf.release_error, udf.coerce_types, volatility);
+        Ok(Self {
+            name,
+            signature,
+            udf,
+        })
+    }
+}
+
+impl ScalarUDFImpl for ForeignScalarUDF {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let mut return_type = call_with_data_types(
+            &self.udf,
+            self.udf.release_error,
+            arg_types,
+            1,
+            self.udf.return_type,
+        )?;
+        Ok(return_type.remove(0))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        call_with_data_types(
+            &self.udf,
+            self.udf.release_error,
+            arg_types,
+            arg_types.len(),
+            self.udf.coerce_types,
+        )
+    }
+
+    fn invoke_batch(&self, args: &[ColumnarValue], number_rows: usize) -> Result<ColumnarValue> {
+        let (mut arrays, schemas): (Vec<_>, Vec<_>) = args
+            .iter()
+            .map(|arg| {
+                let array = arg.clone().into_array(number_rows)?;
+                to_ffi(&array.to_data()).map_err(DataFusionError::from)
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        let mut out_array = FFI_ArrowArray::empty();
+        let mut out_schema = FFI_ArrowSchema::empty();
+        let error = unsafe {
+            (self.udf.invoke)(
+                &self.udf,
+                arrays.as_mut_ptr(),
+                schemas.as_ptr(),
+                arrays.len(),
+                number_rows,
+                &mut out_array,
+                &mut out_schema,
+            )
+        };
+        self.check(error)?;
+
+        let data = unsafe { from_ffi(out_array, &out_schema) }
+            .map_err(|e: ArrowError| DataFusionError::from(e))?;
+        Ok(ColumnarValue::Array(make_array(data)))
+    }
+}
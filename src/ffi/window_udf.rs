@@ -0,0 +1,660 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! C ABI for window UDFs, see the [parent module](super) for how it is used.
+
+use std::any::Any;
+use std::ffi::{c_char, c_void, CString};
+use std::ops::Range;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{make_array, ArrayRef};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::ffi::{from_ffi, to_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use datafusion::common::ScalarValue;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::function::{PartitionEvaluatorArgs, WindowUDFFieldArgs};
+use datafusion::logical_expr::type_coercion::functions::data_types_with_window_udf;
+use datafusion::logical_expr::{PartitionEvaluator, Signature, WindowUDF, WindowUDFImpl};
+
+use super::{
+    arguments_from_c, arguments_to_c, arrays_from_c, arrays_to_c, call_with_data_types,
+    catch_error, check_abi, check_error, data_types_from_c, data_types_to_c, foreign_signature,
+    name_from_c, name_to_c, release_error_fn_wrapper, scalars_from_c, scalars_to_c,
+    volatility_from_c, volatility_to_c, write_coerced_types, FFI_ABI_VERSION,
+};
+
+/// C ABI representation of a window UDF.
+///
+/// Functions returning `*mut c_char` return a null pointer on success and an
+/// error message otherwise. Error messages must be freed with `release_error`.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct FFI_WindowUDF {
+    /// [`FFI_ABI_VERSION`](super::FFI_ABI_VERSION) the struct was built for
+    pub version: u32,
+
+    /// Size of this struct in bytes
+    pub struct_size: usize,
+
+    /// Null terminated name of the function, valid for the lifetime of the struct
+    pub name: unsafe extern "C" fn(udf: &Self) -> *const c_char,
+
+    /// 0 for immutable, 1 for stable and 2 for volatile functions
+    pub volatility: unsafe extern "C" fn(udf: &Self) -> u8,
+
+    /// Coerce `num_args` argument types into the types accepted by the function,
+    /// writing one type for every argument into `out`
+    pub coerce_types: unsafe extern "C" fn(
+        udf: &Self,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        out: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Compute the output field for the (coerced) argument types, given the
+    /// null terminated display name of the call
+    pub field: unsafe extern "C" fn(
+        udf: &Self,
+        arg_types: *const FFI_ArrowSchema,
+        num_args: usize,
+        display_name: *const c_char,
+        out: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Create an evaluator for a partition of the (coerced) arguments, given as
+    /// a single row struct array with one column per argument that holds the
+    /// value of the arguments flagged as literals in `literal_args`
+    pub partition_evaluator: unsafe extern "C" fn(
+        udf: &Self,
+        args: *mut FFI_ArrowArray,
+        args_schema: *const FFI_ArrowSchema,
+        literal_args: *const bool,
+        is_reversed: bool,
+        ignore_nulls: bool,
+        out: *mut FFI_PartitionEvaluator,
+    ) -> *mut c_char,
+
+    /// Free an error message returned by one of the functions above
+    pub release_error: unsafe extern "C" fn(error: *mut c_char),
+
+    /// Create an independent copy of the function
+    pub clone: unsafe extern "C" fn(udf: &Self) -> Self,
+
+    /// Release the resources held by the function
+    pub release: unsafe extern "C" fn(udf: &mut Self),
+
+    /// Data private to the library providing the function
+    pub private_data: *mut c_void,
+}
+
+unsafe impl Send for FFI_WindowUDF {}
+unsafe impl Sync for FFI_WindowUDF {}
+
+/// C ABI representation of a [`PartitionEvaluator`].
+///
+/// The argument values are passed as a single struct array with one column per
+/// argument, whose ownership is moved to the callee. The flags of the
+/// evaluator are read once, when it is created.
+#[repr(C)]
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub struct FFI_PartitionEvaluator {
+    /// [`FFI_ABI_VERSION`](super::FFI_ABI_VERSION) the struct was built for
+    pub version: u32,
+
+    /// Size of this struct in bytes
+    pub struct_size: usize,
+
+    /// Evaluate the function for a whole partition of `num_rows` rows
+    pub evaluate_all: unsafe extern "C" fn(
+        evaluator: &mut Self,
+        values: *mut FFI_ArrowArray,
+        values_schema: *const FFI_ArrowSchema,
+        num_rows: usize,
+        out_array: *mut FFI_ArrowArray,
+        out_schema: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Evaluate the function for the window frame `start..end`, writing the
+    /// value as a single row struct array with one column
+    pub evaluate: unsafe extern "C" fn(
+        evaluator: &mut Self,
+        values: *mut FFI_ArrowArray,
+        values_schema: *const FFI_ArrowSchema,
+        start: usize,
+        end: usize,
+        out_array: *mut FFI_ArrowArray,
+        out_schema: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Evaluate the function from the `num_ranges` ranges of rows with equal
+    /// rank, given as `start, end` pairs in `ranks`
+    pub evaluate_all_with_rank: unsafe extern "C" fn(
+        evaluator: &Self,
+        num_rows: usize,
+        ranks: *const usize,
+        num_ranges: usize,
+        out_array: *mut FFI_ArrowArray,
+        out_schema: *mut FFI_ArrowSchema,
+    ) -> *mut c_char,
+
+    /// Compute the range of rows needed to evaluate row `idx`, written into
+    /// `out_start` and `out_end`
+    pub get_range: unsafe extern "C" fn(
+        evaluator: &Self,
+        idx: usize,
+        num_rows: usize,
+        out_start: *mut usize,
+        out_end: *mut usize,
+    ) -> *mut c_char,
+
+    /// See [`PartitionEvaluator::is_causal`]
+    pub is_causal: bool,
+
+    /// See [`PartitionEvaluator::supports_bounded_execution`]
+    pub supports_bounded_execution: bool,
+
+    /// See [`PartitionEvaluator::uses_window_frame`]
+    pub uses_window_frame: bool,
+
+    /// See [`PartitionEvaluator::include_rank`]
+    pub include_rank: bool,
+
+    /// Free an error message returned by one of the functions above
+    pub release_error: unsafe extern "C" fn(error: *mut c_char),
+
+    /// Release the resources held by the evaluator
+    pub release: unsafe extern "C" fn(evaluator: &mut Self),
+
+    /// Data private to the library providing the evaluator
+    pub private_data: *mut c_void,
+}
+
+unsafe impl Send for FFI_PartitionEvaluator {}
+unsafe impl Sync for FFI_PartitionEvaluator {}
+
+struct WindowUDFPrivateData {
+    udf: Arc<WindowUDF>,
+    name: CString,
+}
+
+unsafe fn private_data(udf: &FFI_WindowUDF) -> &WindowUDFPrivateData {
+    &*(udf.private_data as *const WindowUDFPrivateData)
+}
+
+unsafe extern "C" fn name_fn_wrapper(udf: &FFI_WindowUDF) -> *const c_char {
+    private_data(udf).name.as_ptr()
+}
+
+unsafe extern "C" fn volatility_fn_wrapper(udf: &FFI_WindowUDF) -> u8 {
+    volatility_to_c(private_data(udf).udf.signature().volatility)
+}
+
+unsafe extern "C" fn coerce_types_fn_wrapper(
+    udf: &FFI_WindowUDF,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    out: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        write_coerced_types(arg_types, num_args, out, |arg_types| {
+            data_types_with_window_udf(arg_types, &private_data(udf).udf)
+        })
+    })
+}
+
+unsafe extern "C" fn field_fn_wrapper(
+    udf: &FFI_WindowUDF,
+    arg_types: *const FFI_ArrowSchema,
+    num_args: usize,
+    display_name: *const c_char,
+    out: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let arg_types = data_types_from_c(arg_types, num_args)?;
+        let display_name = name_from_c(display_name);
+        let field = private_data(udf)
+            .udf
+            .field(WindowUDFFieldArgs::new(&arg_types, &display_name))?;
+        out.write(FFI_ArrowSchema::try_from(&field)?);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn partition_evaluator_fn_wrapper(
+    udf: &FFI_WindowUDF,
+    args: *mut FFI_ArrowArray,
+    args_schema: *const FFI_ArrowSchema,
+    literal_args: *const bool,
+    is_reversed: bool,
+    ignore_nulls: bool,
+    out: *mut FFI_PartitionEvaluator,
+) -> *mut c_char {
+    catch_error(|| {
+        let (schema, exprs) = arguments_from_c(args, args_schema, literal_args)?;
+        let arg_types: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect();
+        let evaluator =
+            private_data(udf)
+                .udf
+                .partition_evaluator_factory(PartitionEvaluatorArgs::new(
+                    &exprs,
+                    &arg_types,
+                    is_reversed,
+                    ignore_nulls,
+                ))?;
+        out.write(FFI_PartitionEvaluator::from(evaluator));
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn clone_fn_wrapper(udf: &FFI_WindowUDF) -> FFI_WindowUDF {
+    FFI_WindowUDF::from(Arc::clone(&private_data(udf).udf))
+}
+
+unsafe extern "C" fn release_fn_wrapper(udf: &mut FFI_WindowUDF) {
+    if !udf.private_data.is_null() {
+        drop(Box::from_raw(udf.private_data as *mut WindowUDFPrivateData));
+        udf.private_data = std::ptr::null_mut();
+    }
+}
+
+impl From<Arc<WindowUDF>> for FFI_WindowUDF {
+    fn from(udf: Arc<WindowUDF>) -> Self {
+        let name = name_to_c(udf.name());
+        let private_data = Box::new(WindowUDFPrivateData { udf, name });
+        Self {
+            version: FFI_ABI_VERSION,
+            struct_size: std::mem::size_of::<Self>(),
+            name: name_fn_wrapper,
+            volatility: volatility_fn_wrapper,
+            coerce_types: coerce_types_fn_wrapper,
+            field: field_fn_wrapper,
+            partition_evaluator: partition_evaluator_fn_wrapper,
+            release_error: release_error_fn_wrapper,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            private_data: Box::into_raw(private_data) as *mut c_void,
+        }
+    }
+}
+
+impl Clone for FFI_WindowUDF {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl Drop for FFI_WindowUDF {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+unsafe fn local_evaluator(evaluator: &FFI_PartitionEvaluator) -> &dyn PartitionEvaluator {
+    (*(evaluator.private_data as *const Box<dyn PartitionEvaluator>)).as_ref()
+}
+
+unsafe fn local_evaluator_mut(
+    evaluator: &mut FFI_PartitionEvaluator,
+) -> &mut dyn PartitionEvaluator {
+    (*(evaluator.private_data as *mut Box<dyn PartitionEvaluator>)).as_mut()
+}
+
+unsafe fn write_array(
+    array: ArrayRef,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+) -> Result<()> {
+    let (array, schema) = to_ffi(&array.to_data())?;
+    out_array.write(array);
+    out_schema.write(schema);
+    Ok(())
+}
+
+unsafe extern "C" fn evaluate_all_fn_wrapper(
+    evaluator: &mut FFI_PartitionEvaluator,
+    values: *mut FFI_ArrowArray,
+    values_schema: *const FFI_ArrowSchema,
+    num_rows: usize,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let values = arrays_from_c(values, values_schema)?;
+        let result = local_evaluator_mut(evaluator).evaluate_all(&values, num_rows)?;
+        write_array(result, out_array, out_schema)
+    })
+}
+
+unsafe extern "C" fn evaluate_fn_wrapper(
+    evaluator: &mut FFI_PartitionEvaluator,
+    values: *mut FFI_ArrowArray,
+    values_schema: *const FFI_ArrowSchema,
+    start: usize,
+    end: usize,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let values = arrays_from_c(values, values_schema)?;
+        let value = local_evaluator_mut(evaluator).evaluate(&values, &(start..end))?;
+        let (array, schema) = scalars_to_c(&[value])?;
+        out_array.write(array);
+        out_schema.write(schema);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn evaluate_all_with_rank_fn_wrapper(
+    evaluator: &FFI_PartitionEvaluator,
+    num_rows: usize,
+    ranks: *const usize,
+    num_ranges: usize,
+    out_array: *mut FFI_ArrowArray,
+    out_schema: *mut FFI_ArrowSchema,
+) -> *mut c_char {
+    catch_error(|| {
+        let ranks = if num_ranges == 0 {
+            vec![]
+        } else {
+            std::slice::from_raw_parts(ranks, num_ranges * 2)
+                .chunks_exact(2)
+                .map(|range| range[0]..range[1])
+                .collect()
+        };
+        let result = local_evaluator(evaluator).evaluate_all_with_rank(num_rows, &ranks)?;
+        write_array(result, out_array, out_schema)
+    })
+}
+
+unsafe extern "C" fn get_range_fn_wrapper(
+    evaluator: &FFI_PartitionEvaluator,
+    idx: usize,
+    num_rows: usize,
+    out_start: *mut usize,
+    out_end: *mut usize,
+) -> *mut c_char {
+    catch_error(|| {
+        let range = local_evaluator(evaluator).get_range(idx, num_rows)?;
+        out_start.write(range.start);
+        out_end.write(range.end);
+        Ok(())
+    })
+}
+
+unsafe extern "C" fn release_evaluator_fn_wrapper(evaluator: &mut FFI_PartitionEvaluator) {
+    if !evaluator.private_data.is_null() {
+        drop(Box::from_raw(
+            evaluator.private_data as *mut Box<dyn PartitionEvaluator>,
+        ));
+        evaluator.private_data = std::ptr::null_mut();
+    }
+}
+
+impl From<Box<dyn PartitionEvaluator>> for FFI_PartitionEvaluator {
+    fn from(evaluator: Box<dyn PartitionEvaluator>) -> Self {
+        Self {
+            version: FFI_ABI_VERSION,
+            struct_size: std::mem::size_of::<Self>(),
+            evaluate_all: evaluate_all_fn_wrapper,
+            evaluate: evaluate_fn_wrapper,
+            evaluate_all_with_rank: evaluate_all_with_rank_fn_wrapper,
+            get_range: get_range_fn_wrapper,
+            is_causal: evaluator.is_causal(),
+            supports_bounded_execution: evaluator.supports_bounded_execution(),
+            uses_window_frame: evaluator.uses_window_frame(),
+            include_rank: evaluator.include_rank(),
+            release_error: release_error_fn_wrapper,
+            release: release_evaluator_fn_wrapper,
+            private_data: Box::into_raw(Box::new(evaluator)) as *mut c_void,
+        }
+    }
+}
+
+impl Drop for FFI_PartitionEvaluator {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+/// A partition evaluator provided by another library through a
+/// [`FFI_PartitionEvaluator`]
+#[derive(Debug)]
+struct ForeignPartitionEvaluator {
+    evaluator: FFI_PartitionEvaluator,
+}
+
+impl ForeignPartitionEvaluator {
+    fn check(&self, error: *mut c_char) -> Result<()> {
+        check_error(error, self.evaluator.release_error)
+    }
+
+    fn read_array(
+        &self,
+        error: *mut c_char,
+        array: FFI_ArrowArray,
+        schema: FFI_ArrowSchema,
+    ) -> Result<ArrayRef> {
+        self.check(error)?;
+        let data = unsafe { from_ffi(array, &schema) }?;
+        Ok(make_array(data))
+    }
+}
+
+impl PartitionEvaluator for ForeignPartitionEvaluator {
+    fn evaluate_all(&mut self, values: &[ArrayRef], num_rows: usize) -> Result<ArrayRef> {
+        let (mut values, values_schema) = arrays_to_c(values, num_rows)?;
+        let mut out_array = FFI_ArrowArray::empty();
+        let mut out_schema = FFI_ArrowSchema::empty();
+        let error = unsafe {
+            (self.evaluator.evaluate_all)(
+                &mut self.evaluator,
+                &mut values,
+                &values_schema,
+                num_rows,
+                &mut out_array,
+                &mut out_schema,
+            )
+        };
+        self.read_array(error, out_array, out_schema)
+    }
+
+    fn evaluate(&mut self, values: &[ArrayRef], range: &Range<usize>) -> Result<ScalarValue> {
+        let num_rows = values.first().map(|a| a.len()).unwrap_or_default();
+        let (mut values, values_schema) = arrays_to_c(values, num_rows)?;
+        let mut out_array = FFI_ArrowArray::empty();
+        let mut out_schema = FFI_ArrowSchema::empty();
+        let error = unsafe {
+            (self.evaluator.evaluate)(
+                &mut self.evaluator,
+                &mut values,
+                &values_schema,
+                range.start,
+                range.end,
+                &mut out_array,
+                &mut out_schema,
+            )
+        };
+        self.check(error)?;
+        let mut value = unsafe { scalars_from_c(&mut out_array, &out_schema) }?;
+        if value.len() != 1 {
+            return Err(DataFusionError::Execution(format!(
+                "Expected a single value from foreign partition evaluator. Received {}",
+                value.len()
+            )));
+        }
+        Ok(value.remove(0))
+    }
+
+    fn evaluate_all_with_rank(
+        &self,
+        num_rows: usize,
+        ranks_in_partition: &[Range<usize>],
+    ) -> Result<ArrayRef> {
+        let ranks: Vec<usize> = ranks_in_partition
+            .iter()
+            .flat_map(|range| [range.start, range.end])
+            .collect();
+        let mut out_array = FFI_ArrowArray::empty();
+        let mut out_schema = FFI_ArrowSchema::empty();
+        let error = unsafe {
+            (self.evaluator.evaluate_all_with_rank)(
+                &self.evaluator,
+                num_rows,
+                ranks.as_ptr(),
+                ranks_in_partition.len(),
+                &mut out_array,
+                &mut out_schema,
+            )
+        };
+        self.read_array(error, out_array, out_schema)
+    }
+
+    fn get_range(&self, idx: usize, n_rows: usize) -> Result<Range<usize>> {
+        let mut start = 0;
+        let mut end = 0;
+        let error = unsafe {
+            (self.evaluator.get_range)(&self.evaluator, idx, n_rows, &mut start, &mut end)
+        };
+        self.check(error)?;
+        Ok(start..end)
+    }
+
+    fn is_causal(&self) -> bool {
+        self.evaluator.is_causal
+    }
+
+    fn supports_bounded_execution(&self) -> bool {
+        self.evaluator.supports_bounded_execution
+    }
+
+    fn uses_window_frame(&self) -> bool {
+        self.evaluator.uses_window_frame
+    }
+
+    fn include_rank(&self) -> bool {
+        self.evaluator.include_rank
+    }
+}
+
+/// A window UDF provided by another library through a [`FFI_WindowUDF`]
+#[derive(Debug)]
+pub struct ForeignWindowUDF {
+    name: String,
+    signature: Signature,
+    udf: FFI_WindowUDF,
+}
+
+impl ForeignWindowUDF {
+    fn check(&self, error: *mut c_char) -> Result<()> {
+        check_error(error, self.udf.release_error)
+    }
+}
+
+impl TryFrom<&FFI_WindowUDF> for ForeignWindowUDF {
+    type Error = DataFusionError;
+
+    fn try_from(udf: &FFI_WindowUDF) -> Result<Self> {
+        check_abi::<FFI_WindowUDF>(udf.version, udf.struct_size)?;
+        let udf = udf.clone();
+        let name = unsafe { name_from_c((udf.name)(&udf)) };
+        let volatility = volatility_from_c(unsafe { (udf.volatility)(&udf) }, &name)?;
+        let signature = foreign_signature(&udf, udf.release_error, udf.coerce_types, volatility);
+        Ok(Self {
+            name,
+            signature,
+            udf,
+        })
+    }
+}
+
+impl WindowUDFImpl for ForeignWindowUDF {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        call_with_data_types(
+            &self.udf,
+            self.udf.release_error,
+            arg_types,
+            arg_types.len(),
+            self.udf.coerce_types,
+        )
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<Field> {
+        let arg_types = data_types_to_c(field_args.input_types())?;
+        let display_name = name_to_c(field_args.name());
+        let mut out = FFI_ArrowSchema::empty();
+        let error = unsafe {
+            (self.udf.field)(
+                &self.udf,
+                arg_types.as_ptr(),
+                arg_types.len(),
+                display_name.as_ptr(),
+                &mut out,
+            )
+        };
+        self.check(error)?;
+        Ok(Field::try_from(&out)?)
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let fields: Vec<_> = partition_evaluator_args
+            .input_types()
+            .iter()
+            .enumerate()
+            .map(|(i, t)| Field::new(i.to_string(), t.clone(), true))
+            .collect();
+        let (mut args, args_schema, literal_args) =
+            arguments_to_c(partition_evaluator_args.input_exprs(), &Schema::new(fields))?;
+        let mut out = std::mem::MaybeUninit::<FFI_PartitionEvaluator>::uninit();
+        let error = unsafe {
+            (self.udf.partition_evaluator)(
+                &self.udf,
+                &mut args,
+                &args_schema,
+                literal_args.as_ptr(),
+                partition_evaluator_args.is_reversed(),
+                partition_evaluator_args.ignore_nulls(),
+                out.as_mut_ptr(),
+            )
+        };
+        self.check(error)?;
+        let evaluator = unsafe { out.assume_init() };
+        check_abi::<FFI_PartitionEvaluator>(evaluator.version, evaluator.struct_size)?;
+        Ok(Box::new(ForeignPartitionEvaluator { evaluator }))
+    }
+}
@@ -29,6 +29,7 @@ pub use datafusion::sql as datafusion_sql;
 #[cfg(feature = "substrait")]
 pub use datafusion_substrait;
 
+mod cancellation;
 #[allow(clippy::borrow_deref_ref)]
 pub mod catalog;
 pub mod common;
@@ -37,6 +38,7 @@ pub mod common;
 mod config;
 #[allow(clippy::borrow_deref_ref)]
 pub mod context;
+mod csv_format;
 #[allow(clippy::borrow_deref_ref)]
 pub mod dataframe;
 mod dataset;
@@ -44,6 +46,7 @@ mod dataset_exec;
 pub mod errors;
 #[allow(clippy::borrow_deref_ref)]
 pub mod expr;
+pub mod ffi;
 #[allow(clippy::borrow_deref_ref)]
 mod functions;
 pub mod physical_plan;
@@ -51,6 +54,9 @@ mod pyarrow_filter_expression;
 mod record_batch;
 pub mod sql;
 pub mod store;
+mod streaming_table;
+mod table_provider;
+mod table_provider_exec;
 
 #[cfg(feature = "substrait")]
 pub mod substrait;
@@ -58,6 +64,7 @@ pub mod substrait;
 mod udaf;
 #[allow(clippy::borrow_deref_ref)]
 mod udf;
+mod udtf;
 mod udwf;
 pub mod utils;
 
@@ -75,6 +82,7 @@ pub(crate) struct TokioRuntime(tokio::runtime::Runtime);
 #[pymodule]
 fn _internal(py: Python, m: Bound<'_, PyModule>) -> PyResult<()> {
     // Register the python classes
+    m.add_class::<cancellation::PyCancellationToken>()?;
     m.add_class::<catalog::PyCatalog>()?;
     m.add_class::<catalog::PyDatabase>()?;
     m.add_class::<catalog::PyTable>()?;
@@ -85,6 +93,7 @@ fn _internal(py: Python, m: Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<dataframe::PyDataFrame>()?;
     m.add_class::<udf::PyScalarUDF>()?;
     m.add_class::<udaf::PyAggregateUDF>()?;
+    m.add_class::<udtf::PyTableFunction>()?;
     m.add_class::<udwf::PyWindowUDF>()?;
     m.add_class::<config::PyConfig>()?;
     m.add_class::<sql::logical::PyLogicalPlan>()?;
@@ -92,6 +101,12 @@ fn _internal(py: Python, m: Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<record_batch::PyRecordBatch>()?;
     m.add_class::<record_batch::PyRecordBatchStream>()?;
 
+    // Register the python exceptions
+    m.add(
+        "QueryCancelledError",
+        py.get_type_bound::<errors::QueryCancelledError>(),
+    )?;
+
     // Register `common` as a submodule. Matching `datafusion-common` https://docs.rs/datafusion-common/latest/datafusion_common/
     let common = PyModule::new_bound(py, "common")?;
     common::init_module(&common)?;
@@ -15,13 +15,20 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::utils::wait_for_future;
+use std::sync::Arc;
+
+use crate::errors::DataFusionError;
+use crate::utils::{future_into_py, wait_for_future_cancellable};
 use datafusion::arrow::pyarrow::ToPyArrow;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::physical_plan::SendableRecordBatchStream;
 use futures::StreamExt;
+use pyo3::exceptions::PyStopAsyncIteration;
 use pyo3::prelude::*;
 use pyo3::{pyclass, pymethods, PyObject, PyResult, Python};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 #[pyclass(name = "RecordBatch", module = "datafusion", subclass)]
 pub struct PyRecordBatch {
@@ -43,24 +50,38 @@ impl From<RecordBatch> for PyRecordBatch {
 
 #[pyclass(name = "RecordBatchStream", module = "datafusion", subclass)]
 pub struct PyRecordBatchStream {
-    stream: SendableRecordBatchStream,
+    // Shared so that awaitables returned by `__anext__` can outlive the borrow of self
+    stream: Arc<Mutex<SendableRecordBatchStream>>,
+    token: Option<CancellationToken>,
+    deadline: Option<Instant>,
 }
 
 impl PyRecordBatchStream {
     pub fn new(stream: SendableRecordBatchStream) -> Self {
-        Self { stream }
+        Self {
+            stream: Arc::new(Mutex::new(stream)),
+            token: None,
+            deadline: None,
+        }
+    }
+
+    /// Abort reading from the stream once `token` is cancelled or `deadline` is reached
+    pub(crate) fn with_cancellation(
+        mut self,
+        token: Option<CancellationToken>,
+        deadline: Option<Instant>,
+    ) -> Self {
+        self.token = token;
+        self.deadline = deadline;
+        self
     }
 }
 
 #[pymethods]
 impl PyRecordBatchStream {
     fn next(&mut self, py: Python) -> PyResult<Option<PyRecordBatch>> {
-        let result = self.stream.next();
-        match wait_for_future(py, result) {
-            None => Ok(None),
-            Some(Ok(b)) => Ok(Some(b.into())),
-            Some(Err(e)) => Err(e.into()),
-        }
+        let stream = self.stream.clone();
+        wait_for_future_cancellable(py, next_batch(stream), self.token.as_ref(), self.deadline)?
     }
 
     fn __next__(&mut self, py: Python) -> PyResult<Option<PyRecordBatch>> {
@@ -70,4 +91,29 @@ impl PyRecordBatchStream {
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
     }
+
+    fn __anext__<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let stream = self.stream.clone();
+        future_into_py(py, async move {
+            match next_batch(stream).await? {
+                Some(batch) => Ok(batch),
+                None => Err(PyStopAsyncIteration::new_err("stream exhausted")),
+            }
+        })
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+}
+
+async fn next_batch(
+    stream: Arc<Mutex<SendableRecordBatchStream>>,
+) -> PyResult<Option<PyRecordBatch>> {
+    let mut stream = stream.lock().await;
+    match stream.next().await {
+        None => Ok(None),
+        Some(Ok(b)) => Ok(Some(b.into())),
+        Some(Err(e)) => Err(DataFusionError::from(e).into()),
+    }
 }
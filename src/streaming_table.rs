@@ -0,0 +1,196 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Implements a Datafusion streaming table over record batches that are produced
+/// on demand by a python object, such as an Arrow C stream or a python iterator.
+/// The batches are pulled while the query executes instead of being materialized
+/// up front.
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use futures::{stream, TryStreamExt};
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+
+use datafusion::arrow::array::RecordBatchReader;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::error::Result as ArrowResult;
+use datafusion::arrow::ffi_stream::ArrowArrayStreamReader;
+use datafusion::arrow::pyarrow::FromPyArrow;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::streaming::StreamingTable;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::{DataFusionError as InnerDataFusionError, Result as DFResult};
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream;
+use datafusion::physical_plan::SendableRecordBatchStream;
+
+use crate::errors::DataFusionError;
+use crate::table_provider_exec::python_batches_to_iter;
+
+type BatchIterator = Box<dyn Iterator<Item = ArrowResult<RecordBatch>> + Send>;
+
+// A partition whose record batches are handed out to the first execution only
+pub(crate) struct OneShotPartitionStream {
+    schema: SchemaRef,
+    batches: Mutex<Option<BatchIterator>>,
+}
+
+impl OneShotPartitionStream {
+    pub fn new(schema: SchemaRef, batches: BatchIterator) -> Self {
+        Self {
+            schema,
+            batches: Mutex::new(Some(batches)),
+        }
+    }
+}
+
+impl fmt::Debug for OneShotPartitionStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OneShotPartitionStream")
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+impl PartitionStream for OneShotPartitionStream {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let batches = self
+            .batches
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        match batches {
+            Some(batches) => Box::pin(RecordBatchStreamAdapter::new(
+                self.schema.clone(),
+                stream::iter(batches).map_err(|e| e.into()),
+            )),
+            None => Box::pin(RecordBatchStreamAdapter::new(
+                self.schema.clone(),
+                stream::once(async {
+                    Err(InnerDataFusionError::Execution(
+                        "The record batches of this streaming table have already been consumed"
+                            .to_string(),
+                    ))
+                }),
+            )),
+        }
+    }
+}
+
+// A partition that calls a python factory on every execution to obtain a fresh
+// iterable of record batches
+#[derive(Debug)]
+pub(crate) struct PythonFactoryPartitionStream {
+    schema: SchemaRef,
+    factory: PyObject,
+}
+
+impl PythonFactoryPartitionStream {
+    pub fn new(schema: SchemaRef, factory: PyObject) -> Self {
+        Self { schema, factory }
+    }
+}
+
+impl PartitionStream for PythonFactoryPartitionStream {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let batches = Python::with_gil(|py| {
+            let batches = self.factory.bind(py).call0()?;
+            python_batches_to_iter(&batches)
+        });
+        let schema = self.schema.clone();
+        match batches {
+            Ok(batches) => Box::pin(RecordBatchStreamAdapter::new(
+                schema,
+                stream::iter(batches).map_err(|e| e.into()),
+            )),
+            Err(err) => Box::pin(RecordBatchStreamAdapter::new(
+                schema,
+                stream::once(async { Err(InnerDataFusionError::External(Box::new(err))) }),
+            )),
+        }
+    }
+}
+
+/// Create a table with a single partition that streams `batches` when it is
+/// first scanned
+pub(crate) fn one_shot_streaming_table(
+    schema: SchemaRef,
+    batches: BatchIterator,
+) -> DFResult<StreamingTable> {
+    let partition = Arc::new(OneShotPartitionStream::new(schema.clone(), batches));
+    StreamingTable::try_new(schema, vec![partition])
+}
+
+/// Create a table over a python object that is pulled lazily during execution.
+/// A callable is invoked on every scan to produce the batches, so the table can be
+/// scanned repeatedly, while any other iterable can only be scanned once.
+pub(crate) fn python_streaming_table(
+    schema: SchemaRef,
+    source: &Bound<'_, PyAny>,
+) -> PyResult<StreamingTable> {
+    let partition: Arc<dyn PartitionStream> = if source.is_callable() {
+        Arc::new(PythonFactoryPartitionStream::new(
+            schema.clone(),
+            source.clone().unbind(),
+        ))
+    } else {
+        Arc::new(OneShotPartitionStream::new(
+            schema.clone(),
+            python_batches_to_iter(source)?,
+        ))
+    };
+    let table = StreamingTable::try_new(schema, vec![partition]).map_err(DataFusionError::from)?;
+    Ok(table)
+}
+
+/// Create a table over an object implementing `__arrow_c_stream__` that is read lazily,
+/// or over the single batch of an object implementing `__arrow_c_array__`. An error
+/// raised while exporting the data is returned as is.
+pub(crate) fn arrow_stream_table(data: &Bound<'_, PyAny>) -> PyResult<Arc<dyn TableProvider>> {
+    if data.hasattr("__arrow_c_stream__")? || is_pyarrow_instance(data, "RecordBatchReader")? {
+        let stream_reader = ArrowArrayStreamReader::from_pyarrow_bound(data)?;
+        let schema = stream_reader.schema();
+        let table = one_shot_streaming_table(schema, Box::new(stream_reader))
+            .map_err(DataFusionError::from)?;
+        Ok(Arc::new(table))
+    } else if data.hasattr("__arrow_c_array__")? || is_pyarrow_instance(data, "RecordBatch")? {
+        let batch = RecordBatch::from_pyarrow_bound(data)?;
+        let table =
+            MemTable::try_new(batch.schema(), vec![vec![batch]]).map_err(DataFusionError::from)?;
+        Ok(Arc::new(table))
+    } else {
+        Err(PyTypeError::new_err(
+            "Expected an object implementing __arrow_c_stream__ or __arrow_c_array__",
+        ))
+    }
+}
+
+// pyarrow before version 14 exports its objects without the PyCapsule interface
+fn is_pyarrow_instance(data: &Bound<'_, PyAny>, class: &str) -> PyResult<bool> {
+    let class = data.py().import_bound("pyarrow")?.getattr(class)?;
+    data.is_instance(&class)
+}
@@ -0,0 +1,184 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Implements a Datafusion TableProvider that delegates to a Python object
+/// This allows tables to be implemented in Python while still receiving
+/// projections, filters and limits pushed down by the planner
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::pyarrow::PyArrowType;
+use datafusion::catalog::Session;
+use datafusion::common::stats::Precision;
+use datafusion::common::Statistics;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::{Expr, TableProviderFilterPushDown};
+use datafusion::physical_plan::ExecutionPlan;
+
+use crate::catalog::report_error;
+use crate::expr::PyExpr;
+use crate::table_provider_exec::PythonTableExec;
+
+// Wraps a python object implementing `datafusion.catalog.TableProvider`
+#[derive(Debug)]
+pub(crate) struct PythonTableProvider {
+    provider: PyObject,
+    schema: SchemaRef,
+}
+
+impl PythonTableProvider {
+    pub fn new(provider: &Bound<'_, PyAny>) -> PyResult<Self> {
+        // The schema is requested once up front so that planning does not need
+        // to acquire the GIL every time the schema is inspected
+        let schema = provider
+            .call_method0("schema")?
+            .extract::<PyArrowType<_>>()?
+            .0;
+
+        Ok(Self {
+            provider: provider.clone().unbind(),
+            schema: Arc::new(schema),
+        })
+    }
+
+    /// Get the statistics reported by the python table, if any. They are
+    /// requested once per scan, exceptions being reported through
+    /// `sys.unraisablehook` since missing statistics must not fail a query.
+    fn python_statistics(&self, py: Python) -> Option<Statistics> {
+        let stats = self
+            .provider
+            .bind(py)
+            .call_method0("statistics")
+            .and_then(|v| v.extract::<Option<HashMap<String, usize>>>());
+        let stats = report_error(py, &self.provider, stats)??;
+
+        let mut statistics = Statistics::new_unknown(&self.schema);
+        if let Some(num_rows) = stats.get("num_rows") {
+            statistics.num_rows = Precision::Exact(*num_rows);
+        }
+        if let Some(total_byte_size) = stats.get("total_byte_size") {
+            statistics.total_byte_size = Precision::Exact(*total_byte_size);
+        }
+        Some(statistics)
+    }
+}
+
+/// Convert logical expressions into a python list of `datafusion.Expr`
+pub(crate) fn exprs_to_python<'py>(
+    py: Python<'py>,
+    exprs: &[Expr],
+) -> PyResult<Bound<'py, PyList>> {
+    let expr_class = py.import_bound("datafusion.expr")?.getattr("Expr")?;
+    let exprs = exprs
+        .iter()
+        .map(|expr| expr_class.call1((PyExpr::from(expr.clone()),)))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new_bound(py, exprs))
+}
+
+fn parse_filter_pushdown(value: &str) -> DFResult<TableProviderFilterPushDown> {
+    match value.to_lowercase().as_str() {
+        "unsupported" => Ok(TableProviderFilterPushDown::Unsupported),
+        "inexact" => Ok(TableProviderFilterPushDown::Inexact),
+        "exact" => Ok(TableProviderFilterPushDown::Exact),
+        _ => Err(DataFusionError::Plan(format!(
+            "Unsupported filter pushdown type: `{value}`, supported \
+             values are: unsupported, inexact and exact."
+        ))),
+    }
+}
+
+#[async_trait]
+impl TableProvider for PythonTableProvider {
+    /// Returns the table provider as [`Any`](std::any::Any) so that it can be
+    /// downcast to a specific implementation.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// Get a reference to the schema for this table
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Get the type of this table for metadata/catalog purposes.
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    /// Create an ExecutionPlan that will scan the table. The python `scan`
+    /// method is only invoked once the plan is executed.
+    async fn scan(
+        &self,
+        _ctx: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let plan = Python::with_gil(|py| {
+            PythonTableExec::try_new(
+                self.provider.clone_ref(py),
+                &self.schema,
+                projection.cloned(),
+                filters.to_vec(),
+                limit,
+                self.python_statistics(py),
+            )
+        })?;
+        Ok(Arc::new(plan))
+    }
+
+    /// Tests whether the table provider can make use of a filter expression
+    /// to optimise data retrieval.
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DFResult<Vec<TableProviderFilterPushDown>> {
+        let filters = filters.iter().map(|&f| f.clone()).collect::<Vec<_>>();
+        let pushdown = Python::with_gil(|py| {
+            let py_filters = exprs_to_python(py, &filters)?;
+            self.provider
+                .bind(py)
+                .call_method1("supports_filters_pushdown", (py_filters,))?
+                .iter()?
+                .map(|item| item?.str()?.extract::<String>())
+                .collect::<PyResult<Vec<String>>>()
+        })
+        .map_err(|err| DataFusionError::External(Box::new(err)))?;
+
+        if pushdown.len() != filters.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Expected supports_filters_pushdown to return {} values. Received {}",
+                filters.len(),
+                pushdown.len()
+            )));
+        }
+
+        pushdown
+            .iter()
+            .map(|value| parse_filter_pushdown(value))
+            .collect()
+    }
+}
@@ -0,0 +1,213 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Implements a Datafusion physical ExecutionPlan that delegates to the `scan`
+/// method of a python table provider
+use pyo3::prelude::*;
+use pyo3::types::PyIterator;
+
+use std::any::Any;
+use std::sync::Arc;
+
+use futures::{stream, TryStreamExt};
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::error::Result as ArrowResult;
+use datafusion::arrow::ffi_stream::ArrowArrayStreamReader;
+use datafusion::arrow::pyarrow::FromPyArrow;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError as InnerDataFusionError, Result as DFResult};
+use datafusion::execution::context::TaskContext;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_expr::{EquivalenceProperties, PhysicalSortExpr};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionMode, ExecutionPlan, ExecutionPlanProperties,
+    Partitioning, SendableRecordBatchStream, Statistics,
+};
+
+use crate::dataset_exec::PyArrowBatchesAdapter;
+use crate::table_provider::exprs_to_python;
+
+// Wraps a python table provider and implements a Datafusion ExecutionPlan around it
+#[derive(Debug)]
+pub(crate) struct PythonTableExec {
+    provider: PyObject,
+    schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+    filters: Vec<Expr>,
+    limit: Option<usize>,
+    projected_statistics: Statistics,
+    plan_properties: datafusion::physical_plan::PlanProperties,
+}
+
+impl PythonTableExec {
+    pub fn try_new(
+        provider: PyObject,
+        table_schema: &SchemaRef,
+        projection: Option<Vec<usize>>,
+        filters: Vec<Expr>,
+        limit: Option<usize>,
+        statistics: Option<Statistics>,
+    ) -> DFResult<Self> {
+        let schema = match &projection {
+            Some(p) => Arc::new(table_schema.project(p)?),
+            None => table_schema.clone(),
+        };
+
+        let projected_statistics = statistics
+            .map(|s| s.project(projection.as_ref()))
+            .unwrap_or_else(|| Statistics::new_unknown(&schema));
+        let plan_properties = datafusion::physical_plan::PlanProperties::new(
+            EquivalenceProperties::new(schema.clone()),
+            Partitioning::UnknownPartitioning(1),
+            ExecutionMode::Bounded,
+        );
+
+        Ok(PythonTableExec {
+            provider,
+            schema,
+            projection,
+            filters,
+            limit,
+            projected_statistics,
+            plan_properties,
+        })
+    }
+}
+
+/// Convert the value returned from a python `scan` into an iterator of record batches.
+/// Objects implementing the Arrow PyCapsule stream interface are read directly, any
+/// other iterable is expected to yield `pyarrow.RecordBatch` objects.
+pub(crate) fn python_batches_to_iter(
+    batches: &Bound<'_, PyAny>,
+) -> PyResult<Box<dyn Iterator<Item = ArrowResult<RecordBatch>> + Send>> {
+    if let Ok(reader) = ArrowArrayStreamReader::from_pyarrow_bound(batches) {
+        return Ok(Box::new(reader));
+    }
+
+    let batches: Bound<'_, PyIterator> = batches.iter()?;
+    Ok(Box::new(PyArrowBatchesAdapter {
+        batches: batches.into(),
+    }))
+}
+
+impl ExecutionPlan for PythonTableExec {
+    fn name(&self) -> &str {
+        // [ExecutionPlan::name] docs recommends forwarding to `static_name`
+        Self::static_name()
+    }
+
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// Get the schema for this execution plan
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        // this is a leaf node and has no children
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> DFResult<SendableRecordBatchStream> {
+        let record_batches = Python::with_gil(|py| {
+            let filters = exprs_to_python(py, &self.filters)?;
+            let batches = self
+                .provider
+                .bind(py)
+                .call_method1("scan", (self.projection.clone(), filters, self.limit))?;
+            python_batches_to_iter(&batches)
+        })
+        .map_err(|err| InnerDataFusionError::External(Box::new(err)))?;
+
+        let record_batch_stream = stream::iter(record_batches);
+        let record_batch_stream: SendableRecordBatchStream =
+            Box::pin(RecordBatchStreamAdapter::new(
+                self.schema.clone(),
+                record_batch_stream.map_err(|e| e.into()),
+            ));
+        Ok(record_batch_stream)
+    }
+
+    fn statistics(&self) -> DFResult<Statistics> {
+        Ok(self.projected_statistics.clone())
+    }
+
+    fn properties(&self) -> &datafusion::physical_plan::PlanProperties {
+        &self.plan_properties
+    }
+}
+
+impl ExecutionPlanProperties for PythonTableExec {
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> &Partitioning {
+        self.plan_properties.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn execution_mode(&self) -> datafusion::physical_plan::ExecutionMode {
+        self.plan_properties.execution_mode
+    }
+
+    fn equivalence_properties(&self) -> &datafusion::physical_expr::EquivalenceProperties {
+        &self.plan_properties.eq_properties
+    }
+}
+
+impl DisplayAs for PythonTableExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let projected_columns: Vec<String> = self
+                    .schema
+                    .fields()
+                    .iter()
+                    .map(|x| x.name().to_owned())
+                    .collect();
+                let filters: Vec<String> = self.filters.iter().map(|e| e.to_string()).collect();
+                write!(
+                    f,
+                    "PythonTableExec: projection=[{}], filters=[{}]",
+                    projected_columns.join(", "),
+                    filters.join(", "),
+                )?;
+                if let Some(limit) = self.limit {
+                    write!(f, ", limit={limit}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
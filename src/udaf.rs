@@ -15,42 +15,129 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::any::Any;
 use std::sync::Arc;
 
-use pyo3::{prelude::*, types::PyTuple};
+use pyo3::prelude::*;
+use pyo3::types::{PyCapsule, PyTuple};
 
-use datafusion::arrow::array::{Array, ArrayRef};
-use datafusion::arrow::datatypes::DataType;
-use datafusion::arrow::pyarrow::{PyArrowType, ToPyArrow};
+use datafusion::arrow::array::{make_array, Array, ArrayData, ArrayRef, BooleanArray, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::arrow::pyarrow::{FromPyArrow, PyArrowType, ToPyArrow};
 use datafusion::common::ScalarValue;
 use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion::logical_expr::utils::AggregateOrderSensitivity;
 use datafusion::logical_expr::{
-    create_udaf, Accumulator, AccumulatorFactoryFunction, AggregateUDF,
+    Accumulator, AccumulatorFactoryFunction, AggregateUDF, AggregateUDFImpl, EmitTo,
+    GroupsAccumulator, Signature, Volatility,
 };
 
+use crate::errors::DataFusionError as PyDataFusionError;
 use crate::expr::PyExpr;
-use crate::utils::parse_volatility;
+use crate::ffi::{FFI_AggregateUDF, ForeignAggregateUDF, AGGREGATE_UDF_CAPSULE_NAME};
+use crate::utils::{export_capsule, foreign_capsule, parse_volatility, scalar_value_from_python};
+
+/// Whether the class of a python accumulator overrides the `size` method of
+/// `base`, its base class in `datafusion.udf`
+fn overrides_size(accum: &Bound<'_, PyAny>, base: &str) -> PyResult<bool> {
+    let base = accum.py().import_bound("datafusion.udf")?.getattr(base)?;
+    Ok(!accum.get_type().getattr("size")?.is(&base.getattr("size")?))
+}
+
+/// Fixed estimate of the size of a state holding values of `state_types`
+fn estimated_state_size(state_types: &[DataType]) -> usize {
+    state_types
+        .iter()
+        .map(|data_type| {
+            ScalarValue::try_from(data_type)
+                .map(|value| value.size())
+                .unwrap_or(std::mem::size_of::<ScalarValue>())
+        })
+        .sum()
+}
 
 #[derive(Debug)]
 struct RustAccumulator {
     accum: PyObject,
+    state_types: Vec<DataType>,
+    return_type: DataType,
+    /// Whether the python accumulator reports its own size, checked once since
+    /// calling into python after every batch is costly
+    reports_size: bool,
+    /// Size of the state, refreshed whenever it changes if the python
+    /// accumulator reports it and a fixed estimate otherwise
+    size: usize,
 }
 
 impl RustAccumulator {
-    fn new(accum: PyObject) -> Self {
-        Self { accum }
+    fn new(
+        accum: PyObject,
+        state_types: Vec<DataType>,
+        return_type: DataType,
+        reports_size: bool,
+    ) -> Self {
+        let size = estimated_state_size(&state_types);
+        Self {
+            accum,
+            state_types,
+            return_type,
+            reports_size,
+            size,
+        }
+    }
+
+    /// Refresh the size reported by the python accumulator, keeping the fixed
+    /// estimate when it returns `None`
+    fn update_size(&mut self, py: Python) -> Result<()> {
+        if !self.reports_size {
+            return Ok(());
+        }
+        let size = self
+            .accum
+            .bind(py)
+            .call_method0("size")
+            .and_then(|size| size.extract::<Option<usize>>())
+            .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+        self.size = size.unwrap_or_else(|| estimated_state_size(&self.state_types));
+        Ok(())
     }
 }
 
 impl Accumulator for RustAccumulator {
     fn state(&mut self) -> Result<Vec<ScalarValue>> {
-        Python::with_gil(|py| self.accum.bind(py).call_method0("state")?.extract())
-            .map_err(|e| DataFusionError::Execution(format!("{e}")))
+        let state = Python::with_gil(|py| {
+            self.accum
+                .bind(py)
+                .call_method0("state")?
+                .iter()?
+                .zip(
+                    self.state_types
+                        .iter()
+                        .map(Some)
+                        .chain(std::iter::repeat(None)),
+                )
+                .map(|(value, data_type)| scalar_value_from_python(&value?, data_type))
+                .collect::<PyResult<Vec<_>>>()
+        })
+        .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
+
+        if state.len() != self.state_types.len() {
+            return Err(DataFusionError::Execution(format!(
+                "Expected the accumulator state to have {} values. Received {}",
+                self.state_types.len(),
+                state.len()
+            )));
+        }
+        Ok(state)
     }
 
     fn evaluate(&mut self) -> Result<ScalarValue> {
-        Python::with_gil(|py| self.accum.bind(py).call_method0("evaluate")?.extract())
-            .map_err(|e| DataFusionError::Execution(format!("{e}")))
+        Python::with_gil(|py| {
+            let value = self.accum.bind(py).call_method0("evaluate")?;
+            scalar_value_from_python(&value, Some(&self.return_type))
+        })
+        .map_err(|e| DataFusionError::Execution(format!("{e}")))
     }
 
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
@@ -68,7 +155,7 @@ impl Accumulator for RustAccumulator {
                 .call_method1("update", py_args)
                 .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
 
-            Ok(())
+            self.update_size(py)
         })
     }
 
@@ -91,12 +178,12 @@ impl Accumulator for RustAccumulator {
                 .call_method1("merge", (py_states?,))
                 .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
 
-            Ok(())
+            self.update_size(py)
         })
     }
 
     fn size(&self) -> usize {
-        std::mem::size_of_val(self)
+        std::mem::size_of_val(self) + self.size
     }
 
     fn retract_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
@@ -114,7 +201,7 @@ impl Accumulator for RustAccumulator {
                 .call_method1("retract_batch", py_args)
                 .map_err(|e| DataFusionError::Execution(format!("{e}")))?;
 
-            Ok(())
+            self.update_size(py)
         })
     }
 
@@ -128,17 +215,242 @@ impl Accumulator for RustAccumulator {
     }
 }
 
-pub fn to_rust_accumulator(accum: PyObject) -> AccumulatorFactoryFunction {
+pub fn to_rust_accumulator(
+    accum: PyObject,
+    state_types: Vec<DataType>,
+    return_type: DataType,
+) -> AccumulatorFactoryFunction {
     Arc::new(move |_| -> Result<Box<dyn Accumulator>> {
-        let accum = Python::with_gil(|py| {
-            accum
-                .call0(py)
-                .map_err(|e| DataFusionError::Execution(format!("{e}")))
-        })?;
-        Ok(Box::new(RustAccumulator::new(accum)))
+        let (accum, reports_size) = Python::with_gil(|py| {
+            let accum = accum.bind(py).call0()?;
+            let reports_size = overrides_size(&accum, "Accumulator")?;
+            Ok((accum.unbind(), reports_size))
+        })
+        .map_err(|e: PyErr| DataFusionError::Execution(format!("{e}")))?;
+        Ok(Box::new(RustAccumulator::new(
+            accum,
+            state_types.clone(),
+            return_type.clone(),
+            reports_size,
+        )))
     })
 }
 
+/// Implements a Datafusion GroupsAccumulator that delegates to a python object
+/// which accumulates the state of all groups at once
+#[derive(Debug)]
+struct RustGroupsAccumulator {
+    accum: PyObject,
+    /// Whether the python accumulator reports its own size, checked once since
+    /// calling into python after every batch is costly
+    reports_size: bool,
+    /// Size reported by the python accumulator, refreshed whenever its state
+    /// changes
+    size: usize,
+}
+
+impl RustGroupsAccumulator {
+    fn new(accum: PyObject, reports_size: bool) -> Self {
+        Self {
+            accum,
+            reports_size,
+            size: 0,
+        }
+    }
+
+    fn update_size(&mut self, py: Python) -> PyResult<()> {
+        if self.reports_size {
+            self.size = self.accum.bind(py).call_method0("size")?.extract()?;
+        }
+        Ok(())
+    }
+
+    fn call_with_groups(
+        &mut self,
+        method: &str,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        Python::with_gil(|py| {
+            // 1. cast values, group indices and filter to Pyarrow arrays
+            let py_values = values
+                .iter()
+                .map(|value| value.into_data().to_pyarrow(py))
+                .collect::<PyResult<Vec<_>>>()?;
+            let group_indices =
+                UInt64Array::from_iter_values(group_indices.iter().map(|&i| i as u64));
+            let py_group_indices = group_indices.into_data().to_pyarrow(py)?;
+            let py_filter = opt_filter
+                .map(|filter| filter.into_data().to_pyarrow(py))
+                .transpose()?;
+
+            // 2. call function
+            self.accum.bind(py).call_method1(
+                method,
+                (py_values, py_group_indices, py_filter, total_num_groups),
+            )?;
+            self.update_size(py)
+        })
+        .map_err(|e: PyErr| DataFusionError::Execution(format!("{e}")))
+    }
+}
+
+/// `EmitTo::All` is passed to python as `None` and `EmitTo::First(n)` as `n`
+fn emit_to_python(emit_to: EmitTo) -> Option<usize> {
+    match emit_to {
+        EmitTo::All => None,
+        EmitTo::First(n) => Some(n),
+    }
+}
+
+impl GroupsAccumulator for RustGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.call_with_groups(
+            "update_batch",
+            values,
+            group_indices,
+            opt_filter,
+            total_num_groups,
+        )
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        Python::with_gil(|py| {
+            let array = self
+                .accum
+                .bind(py)
+                .call_method1("evaluate", (emit_to_python(emit_to),))?;
+            let array = make_array(ArrayData::from_pyarrow_bound(&array)?);
+            self.update_size(py)?;
+            Ok(array)
+        })
+        .map_err(|e: PyErr| DataFusionError::Execution(format!("{e}")))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        Python::with_gil(|py| {
+            let state = self
+                .accum
+                .bind(py)
+                .call_method1("state", (emit_to_python(emit_to),))?
+                .iter()?
+                .map(|array| Ok(make_array(ArrayData::from_pyarrow_bound(&array?)?)))
+                .collect::<PyResult<Vec<_>>>()?;
+            self.update_size(py)?;
+            Ok(state)
+        })
+        .map_err(|e: PyErr| DataFusionError::Execution(format!("{e}")))
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.call_with_groups(
+            "merge_batch",
+            values,
+            group_indices,
+            opt_filter,
+            total_num_groups,
+        )
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.size
+    }
+}
+
+/// Implements [`AggregateUDFImpl`] for python accumulators, using a python
+/// `GroupsAccumulator` for grouped aggregation when one is provided
+#[derive(Debug)]
+struct PythonAggregateUDF {
+    name: String,
+    signature: Signature,
+    return_type: DataType,
+    state_fields: Vec<Field>,
+    accumulator: PyObject,
+    groups_accumulator: Option<PyObject>,
+    order_sensitive: bool,
+}
+
+impl AggregateUDFImpl for PythonAggregateUDF {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        let accumulator = Python::with_gil(|py| self.accumulator.clone_ref(py));
+        let state_types = self
+            .state_fields
+            .iter()
+            .map(|field| field.data_type().clone())
+            .collect();
+        let accumulator = to_rust_accumulator(accumulator, state_types, self.return_type.clone());
+        accumulator(acc_args)
+    }
+
+    fn state_fields(&self, _args: StateFieldsArgs) -> Result<Vec<Field>> {
+        Ok(self.state_fields.clone())
+    }
+
+    /// Order sensitive accumulators receive their input sorted by the `ORDER BY`
+    /// of the aggregate, with the values of the ordering expressions appended
+    /// to the arguments of `update`. Otherwise any ordering is ignored.
+    fn order_sensitivity(&self) -> AggregateOrderSensitivity {
+        if self.order_sensitive {
+            AggregateOrderSensitivity::HardRequirement
+        } else {
+            AggregateOrderSensitivity::Insensitive
+        }
+    }
+
+    fn groups_accumulator_supported(&self, args: AccumulatorArgs) -> bool {
+        self.groups_accumulator.is_some() && !args.is_distinct
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        let Some(factory) = &self.groups_accumulator else {
+            return Err(DataFusionError::NotImplemented(format!(
+                "{} does not provide a GroupsAccumulator",
+                self.name
+            )));
+        };
+        let (accum, reports_size) = Python::with_gil(|py| {
+            let accum = factory.bind(py).call0()?;
+            let reports_size = overrides_size(&accum, "GroupsAccumulator")?;
+            Ok((accum.unbind(), reports_size))
+        })
+        .map_err(|e: PyErr| DataFusionError::Execution(format!("{e}")))?;
+        Ok(Box::new(RustGroupsAccumulator::new(accum, reports_size)))
+    }
+}
+
 /// Represents an AggregateUDF
 #[pyclass(name = "AggregateUDF", module = "datafusion", subclass)]
 #[derive(Debug, Clone)]
@@ -149,7 +461,8 @@ pub struct PyAggregateUDF {
 #[pymethods]
 impl PyAggregateUDF {
     #[new]
-    #[pyo3(signature=(name, accumulator, input_type, return_type, state_type, volatility))]
+    #[pyo3(signature=(name, accumulator, input_type, return_type, state_type, volatility, groups_accumulator=None, order_sensitive=false))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         name: &str,
         accumulator: PyObject,
@@ -157,15 +470,25 @@ impl PyAggregateUDF {
         return_type: PyArrowType<DataType>,
         state_type: PyArrowType<Vec<DataType>>,
         volatility: &str,
+        groups_accumulator: Option<PyObject>,
+        order_sensitive: bool,
     ) -> PyResult<Self> {
-        let function = create_udaf(
-            name,
-            input_type.0,
-            Arc::new(return_type.0),
-            parse_volatility(volatility)?,
-            to_rust_accumulator(accumulator),
-            Arc::new(state_type.0),
-        );
+        let volatility: Volatility = parse_volatility(volatility)?;
+        let state_fields = state_type
+            .0
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| Field::new(format!("{i}"), t, true))
+            .collect();
+        let function = AggregateUDF::new_from_impl(PythonAggregateUDF {
+            name: name.to_owned(),
+            signature: Signature::exact(input_type.0, volatility),
+            return_type: return_type.0,
+            state_fields,
+            accumulator,
+            groups_accumulator,
+            order_sensitive,
+        });
         Ok(Self { function })
     }
 
@@ -176,6 +499,29 @@ impl PyAggregateUDF {
         Ok(self.function.call(args).into())
     }
 
+    /// Create a UDAF from an object implemented in another Rust library. The
+    /// object must either be a `datafusion_aggregate_udf` PyCapsule or provide
+    /// one from a `__datafusion_aggregate_udf__` method.
+    #[staticmethod]
+    fn from_pycapsule(func: Bound<'_, PyAny>) -> PyResult<Self> {
+        let capsule = foreign_capsule(func, AGGREGATE_UDF_CAPSULE_NAME)?;
+        let udaf = unsafe { &*(capsule.pointer() as *const FFI_AggregateUDF) };
+        let udaf = ForeignAggregateUDF::try_from(udaf).map_err(PyDataFusionError::from)?;
+        Ok(Self {
+            function: AggregateUDF::new_from_impl(udaf),
+        })
+    }
+
+    /// Export the UDAF in a `datafusion_aggregate_udf` PyCapsule, to be used by
+    /// other libraries
+    fn __datafusion_aggregate_udf__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        let udaf = FFI_AggregateUDF::from(Arc::new(self.function.clone()));
+        export_capsule(py, udaf, AGGREGATE_UDF_CAPSULE_NAME)
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!("AggregateUDF({})", self.function.name()))
     }
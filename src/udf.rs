@@ -15,21 +15,25 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::any::Any;
+use std::fmt;
 use std::sync::Arc;
 
-use pyo3::{prelude::*, types::PyTuple};
+use pyo3::prelude::*;
+use pyo3::types::{PyCapsule, PyTuple};
 
 use datafusion::arrow::array::{make_array, Array, ArrayData, ArrayRef};
 use datafusion::arrow::datatypes::DataType;
 use datafusion::arrow::pyarrow::FromPyArrow;
 use datafusion::arrow::pyarrow::{PyArrowType, ToPyArrow};
-use datafusion::error::DataFusionError;
+use datafusion::error::{DataFusionError, Result};
 use datafusion::logical_expr::function::ScalarFunctionImplementation;
-use datafusion::logical_expr::ScalarUDF;
-use datafusion::logical_expr::{create_udf, ColumnarValue};
+use datafusion::logical_expr::{ColumnarValue, ScalarUDF, ScalarUDFImpl, Signature, Volatility};
 
+use crate::errors::DataFusionError as PyDataFusionError;
 use crate::expr::PyExpr;
-use crate::utils::parse_volatility;
+use crate::ffi::{FFI_ScalarUDF, ForeignScalarUDF, SCALAR_UDF_CAPSULE_NAME};
+use crate::utils::{export_capsule, foreign_capsule, parse_volatility, scalar_value_from_python};
 
 /// Create a Rust callable function from a python function that expects pyarrow arrays
 fn pyarrow_function_to_rust(
@@ -76,6 +80,131 @@ fn to_scalar_function_impl(func: PyObject) -> ScalarFunctionImplementation {
     })
 }
 
+/// Create a DataFusion's UDF implementation from a python function that
+/// receives scalar arguments as pyarrow scalars instead of broadcasting them
+/// into arrays, and which may return either a pyarrow array or scalar.
+fn to_scalar_preserving_function_impl(func: PyObject) -> ScalarFunctionImplementation {
+    Arc::new(move |args: &[ColumnarValue]| {
+        Python::with_gil(|py| {
+            // 1. cast args to Pyarrow arrays or scalars
+            let py_args = args
+                .iter()
+                .map(|arg| match arg {
+                    ColumnarValue::Array(array) => array.into_data().to_pyarrow(py),
+                    ColumnarValue::Scalar(scalar) => scalar.to_pyarrow(py),
+                })
+                .collect::<PyResult<Vec<_>>>()
+                .map_err(|e| DataFusionError::Execution(format!("{e:?}")))?;
+            let py_args = PyTuple::new_bound(py, py_args);
+
+            // 2. call function
+            let value = func
+                .call_bound(py, py_args, None)
+                .map_err(|e| DataFusionError::Execution(format!("{e:?}")))?;
+            let value = value.bind(py);
+
+            // 3. cast to a datafusion ColumnarValue
+            let is_scalar = py
+                .import_bound("pyarrow")
+                .and_then(|pa| value.is_instance(&pa.getattr("Scalar")?))
+                .map_err(|e| DataFusionError::Execution(format!("{e:?}")))?;
+            let result = if is_scalar {
+                scalar_value_from_python(value, None).map(ColumnarValue::Scalar)
+            } else {
+                ArrayData::from_pyarrow_bound(value).map(|data| make_array(data).into())
+            };
+            result.map_err(|e| DataFusionError::Execution(format!("{e:?}")))
+        })
+    })
+}
+
+/// The return type of a python UDF, either fixed up front or computed from the
+/// argument types by a python callback
+#[derive(Debug)]
+enum ReturnType {
+    Fixed(DataType),
+    Callback(PyObject),
+}
+
+/// Call a python callback taking a list of pyarrow data types
+fn call_with_data_types<'py>(
+    py: Python<'py>,
+    func: &PyObject,
+    arg_types: &[DataType],
+) -> PyResult<Bound<'py, PyAny>> {
+    let arg_types = arg_types
+        .iter()
+        .map(|t| t.to_pyarrow(py))
+        .collect::<PyResult<Vec<_>>>()?;
+    func.bind(py).call1((arg_types,))
+}
+
+/// Implements [`ScalarUDFImpl`] for a python function, optionally delegating
+/// type resolution to python `return_type` and `coerce_types` callbacks
+struct PythonFunctionScalarUDF {
+    name: String,
+    signature: Signature,
+    return_type: ReturnType,
+    coerce_types: Option<PyObject>,
+    fun: ScalarFunctionImplementation,
+}
+
+impl fmt::Debug for PythonFunctionScalarUDF {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PythonFunctionScalarUDF")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .field("return_type", &self.return_type)
+            .field("coerce_types", &self.coerce_types)
+            .finish()
+    }
+}
+
+impl ScalarUDFImpl for PythonFunctionScalarUDF {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &self.return_type {
+            ReturnType::Fixed(return_type) => Ok(return_type.clone()),
+            ReturnType::Callback(func) => Python::with_gil(|py| {
+                Ok(call_with_data_types(py, func, arg_types)?
+                    .extract::<PyArrowType<DataType>>()?
+                    .0)
+            })
+            .map_err(|e: PyErr| DataFusionError::External(Box::new(e))),
+        }
+    }
+
+    fn invoke_batch(&self, args: &[ColumnarValue], _number_rows: usize) -> Result<ColumnarValue> {
+        (self.fun)(args)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let Some(func) = &self.coerce_types else {
+            return Err(DataFusionError::Plan(format!(
+                "Function {} does not provide coerce_types",
+                self.name
+            )));
+        };
+        Python::with_gil(|py| {
+            Ok(call_with_data_types(py, func, arg_types)?
+                .extract::<PyArrowType<Vec<DataType>>>()?
+                .0)
+        })
+        .map_err(|e: PyErr| DataFusionError::External(Box::new(e)))
+    }
+}
+
 /// Represents a PyScalarUDF
 #[pyclass(name = "ScalarUDF", module = "datafusion", subclass)]
 #[derive(Debug, Clone)]
@@ -85,25 +214,70 @@ pub struct PyScalarUDF {
 
 #[pymethods]
 impl PyScalarUDF {
+    /// Both `input_types` and `return_type` may either be pyarrow data types or
+    /// python callbacks. A callable `input_types` acts as `coerce_types`, which
+    /// maps the argument types of a call to the types the function accepts, and
+    /// a callable `return_type` computes the return type from the argument types.
     #[new]
-    #[pyo3(signature=(name, func, input_types, return_type, volatility))]
+    #[pyo3(signature=(name, func, input_types, return_type, volatility, scalar_args=false))]
     fn new(
         name: &str,
         func: PyObject,
-        input_types: PyArrowType<Vec<DataType>>,
-        return_type: PyArrowType<DataType>,
+        input_types: Bound<'_, PyAny>,
+        return_type: Bound<'_, PyAny>,
         volatility: &str,
+        scalar_args: bool,
     ) -> PyResult<Self> {
-        let function = create_udf(
-            name,
-            input_types.0,
-            return_type.0,
-            parse_volatility(volatility)?,
-            to_scalar_function_impl(func),
-        );
+        let volatility: Volatility = parse_volatility(volatility)?;
+        let (signature, coerce_types) = if input_types.is_callable() {
+            (
+                Signature::user_defined(volatility),
+                Some(input_types.unbind()),
+            )
+        } else {
+            let input_types = input_types.extract::<PyArrowType<Vec<DataType>>>()?.0;
+            (Signature::exact(input_types, volatility), None)
+        };
+        let return_type = if return_type.is_callable() {
+            ReturnType::Callback(return_type.unbind())
+        } else {
+            ReturnType::Fixed(return_type.extract::<PyArrowType<DataType>>()?.0)
+        };
+        let fun = if scalar_args {
+            to_scalar_preserving_function_impl(func)
+        } else {
+            to_scalar_function_impl(func)
+        };
+        let function = ScalarUDF::new_from_impl(PythonFunctionScalarUDF {
+            name: name.to_owned(),
+            signature,
+            return_type,
+            coerce_types,
+            fun,
+        });
         Ok(Self { function })
     }
 
+    /// Create a UDF from an object implemented in another Rust library. The
+    /// object must either be a `datafusion_scalar_udf` PyCapsule or provide one
+    /// from a `__datafusion_scalar_udf__` method.
+    #[staticmethod]
+    fn from_pycapsule(func: Bound<'_, PyAny>) -> PyResult<Self> {
+        let capsule = foreign_capsule(func, SCALAR_UDF_CAPSULE_NAME)?;
+        let udf = unsafe { &*(capsule.pointer() as *const FFI_ScalarUDF) };
+        let udf = ForeignScalarUDF::try_from(udf).map_err(PyDataFusionError::from)?;
+        Ok(Self {
+            function: ScalarUDF::new_from_impl(udf),
+        })
+    }
+
+    /// Export the UDF in a `datafusion_scalar_udf` PyCapsule, to be used by
+    /// other libraries
+    fn __datafusion_scalar_udf__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+        let udf = FFI_ScalarUDF::from(Arc::new(self.function.clone()));
+        export_capsule(py, udf, SCALAR_UDF_CAPSULE_NAME)
+    }
+
     /// creates a new PyExpr with the call of the udf
     #[pyo3(signature = (*args))]
     fn __call__(&self, args: Vec<PyExpr>) -> PyResult<PyExpr> {
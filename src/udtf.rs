@@ -0,0 +1,95 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+
+use datafusion::arrow::pyarrow::PyArrowType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::function::TableFunctionImpl;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::Expr;
+
+use crate::catalog::table_provider_from_python;
+use crate::streaming_table::arrow_stream_table;
+use crate::table_provider::exprs_to_python;
+
+/// Implements a DataFusion table function by calling a python function with the
+/// arguments of the call as `datafusion.Expr`
+#[derive(Debug)]
+struct PythonTableFunction {
+    func: PyObject,
+}
+
+impl TableFunctionImpl for PythonTableFunction {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        Python::with_gil(|py| {
+            let args = PyTuple::new_bound(py, exprs_to_python(py, args)?);
+            let table = self.func.bind(py).call1(args)?;
+            table_function_result_to_provider(&table)
+        })
+        .map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+}
+
+/// Convert the value returned by a python table function into a `TableProvider`.
+/// Lists of record batches are registered in memory, Arrow streams are read lazily
+/// and anything else must be a table or implement `datafusion.catalog.TableProvider`.
+fn table_function_result_to_provider(table: &Bound<'_, PyAny>) -> PyResult<Arc<dyn TableProvider>> {
+    if table.is_instance_of::<PyList>() {
+        let batches = table.extract::<PyArrowType<Vec<RecordBatch>>>()?.0;
+        let Some(schema) = batches.first().map(|batch| batch.schema()) else {
+            return Err(PyValueError::new_err(
+                "A table function must return at least one record batch",
+            ));
+        };
+        let table = MemTable::try_new(schema, vec![batches])?;
+        return Ok(Arc::new(table));
+    }
+    if table.hasattr("__arrow_c_stream__")? || table.hasattr("__arrow_c_array__")? {
+        return arrow_stream_table(table);
+    }
+    table_provider_from_python(table)
+}
+
+/// Represents a PyTableFunction
+#[pyclass(name = "TableFunction", module = "datafusion", subclass)]
+#[derive(Debug, Clone)]
+pub struct PyTableFunction {
+    pub(crate) name: String,
+    pub(crate) function: Arc<dyn TableFunctionImpl>,
+}
+
+#[pymethods]
+impl PyTableFunction {
+    #[new]
+    #[pyo3(signature=(name, func))]
+    fn new(name: &str, func: PyObject) -> Self {
+        Self {
+            name: name.to_owned(),
+            function: Arc::new(PythonTableFunction { func }),
+        }
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("TableFunction({})", self.name))
+    }
+}
@@ -28,15 +28,17 @@ use datafusion_functions_window_common::expr::ExpressionArgs;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
+use crate::errors::DataFusionError as PyDataFusionError;
 use crate::expr::PyExpr;
-use crate::utils::parse_volatility;
+use crate::ffi::{FFI_WindowUDF, ForeignWindowUDF, WINDOW_UDF_CAPSULE_NAME};
+use crate::utils::{export_capsule, foreign_capsule, parse_volatility};
 use datafusion::arrow::datatypes::DataType;
 use datafusion::arrow::pyarrow::{FromPyArrow, PyArrowType, ToPyArrow};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::logical_expr::{
     PartitionEvaluator, PartitionEvaluatorFactory, Signature, Volatility, WindowUDF, WindowUDFImpl,
 };
-use pyo3::types::{PyList, PyTuple};
+use pyo3::types::{PyCapsule, PyList, PyTuple};
 
 #[derive(Debug)]
 struct RustPartitionEvaluator {
@@ -247,6 +249,26 @@ impl PyWindowUDF {
         Ok(self.function.call(args).into())
     }
 
+    /// Create a UDWF from an object implemented in another Rust library. The
+    /// object must either be a `datafusion_window_udf` PyCapsule or provide one
+    /// from a `__datafusion_window_udf__` method.
+    #[staticmethod]
+    fn from_pycapsule(func: Bound<'_, PyAny>) -> PyResult<Self> {
+        let capsule = foreign_capsule(func, WINDOW_UDF_CAPSULE_NAME)?;
+        let udwf = unsafe { &*(capsule.pointer() as *const FFI_WindowUDF) };
+        let udwf = ForeignWindowUDF::try_from(udwf).map_err(PyDataFusionError::from)?;
+        Ok(Self {
+            function: WindowUDF::new_from_impl(udwf),
+        })
+    }
+
+    /// Export the UDWF in a `datafusion_window_udf` PyCapsule, to be used by
+    /// other libraries
+    fn __datafusion_window_udf__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+        let udwf = FFI_WindowUDF::from(Arc::new(self.function.clone()));
+        export_capsule(py, udwf, WINDOW_UDF_CAPSULE_NAME)
+    }
+
     fn __repr__(&self) -> PyResult<String> {
         Ok(format!("WindowUDF({})", self.function.name()))
     }
@@ -15,24 +15,114 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::errors::DataFusionError;
+use crate::dataframe::validate_pycapsule;
+use crate::errors::{DataFusionError, QueryCancelledError};
 use crate::TokioRuntime;
+use datafusion::arrow::array::{make_array, ArrayData};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::pyarrow::{FromPyArrow, ToPyArrow};
+use datafusion::common::ScalarValue;
 use datafusion::logical_expr::Volatility;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
+use pyo3_async_runtimes::generic::{ContextExt, Runtime as GenericRuntime};
+use pyo3_async_runtimes::TaskLocals;
+use std::cell::OnceCell;
+use std::ffi::CString;
 use std::future::Future;
-use std::sync::OnceLock;
+use std::pin::Pin;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::time::Duration;
 use tokio::runtime::Runtime;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// How often a blocked call re-acquires the GIL to look for pending signals
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of worker threads used when building the Tokio Runtime, 0 means the
+/// tokio default of one thread per core
+static WORKER_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// The Tokio Runtime together with the id of the process that created it
+struct ProcessRuntime {
+    pid: u32,
+    runtime: TokioRuntime,
+}
+
+/// The runtime is read without taking a lock, as a lock held by another thread
+/// while the process forks would never be released in the child. It points to a
+/// leaked `ProcessRuntime` once a runtime has been built.
+static RUNTIME: AtomicPtr<ProcessRuntime> = AtomicPtr::new(ptr::null_mut());
+
+/// The runtime built by the process `pid`, if any
+fn process_runtime(pid: u32) -> Option<&'static TokioRuntime> {
+    // SAFETY: the pointer is null or points to a `ProcessRuntime` that is never freed
+    let current = unsafe { RUNTIME.load(Ordering::Acquire).as_ref() }?;
+    (current.pid == pid).then_some(&current.runtime)
+}
 
 /// Utility to get the Tokio Runtime from Python
+///
+/// A runtime inherited through `fork` (e.g. from a prefork app-server like
+/// `gunicorn`) has lost all of its worker threads, so a new runtime is built
+/// whenever the current process id differs from the one that created it.
 #[inline]
 pub(crate) fn get_tokio_runtime() -> &'static TokioRuntime {
-    // NOTE: Other pyo3 python libraries have had issues with using tokio
-    // behind a forking app-server like `gunicorn`
-    // If we run into that problem, in the future we can look to `delta-rs`
-    // which adds a check in that disallows calls from a forked process
-    // https://github.com/delta-io/delta-rs/blob/87010461cfe01563d91a4b9cd6fa468e2ad5f283/python/src/utils.rs#L10-L31
-    static RUNTIME: OnceLock<TokioRuntime> = OnceLock::new();
-    RUNTIME.get_or_init(|| TokioRuntime(tokio::runtime::Runtime::new().unwrap()))
+    let pid = std::process::id();
+    if let Some(runtime) = process_runtime(pid) {
+        return runtime;
+    }
+    // The runtime of the parent process is leaked rather than dropped, as
+    // shutting it down would wait on threads that do not exist in this process
+    let new = Box::into_raw(Box::new(ProcessRuntime {
+        pid,
+        runtime: TokioRuntime(build_runtime()),
+    }));
+    let mut current = RUNTIME.load(Ordering::Acquire);
+    loop {
+        if let Some(runtime) = process_runtime(pid) {
+            // another thread built a runtime first
+            // SAFETY: `new` was never shared
+            let unused = unsafe { Box::from_raw(new) };
+            unused.runtime.0.shutdown_background();
+            return runtime;
+        }
+        match RUNTIME.compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire) {
+            // SAFETY: `new` is leaked and never freed once it is published
+            Ok(_) => return unsafe { &(*new).runtime },
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+fn build_runtime() -> Runtime {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    match WORKER_THREADS.load(Ordering::SeqCst) {
+        0 => &mut builder,
+        threads => builder.worker_threads(threads),
+    }
+    .enable_all()
+    .build()
+    .unwrap()
+}
+
+/// Set the number of worker threads of the Tokio Runtime. This must happen
+/// before the runtime is first used by the current process.
+pub(crate) fn set_tokio_worker_threads(threads: usize) -> PyResult<()> {
+    let current = WORKER_THREADS.load(Ordering::SeqCst);
+    if current == threads {
+        return Ok(());
+    }
+    if process_runtime(std::process::id()).is_some() {
+        return Err(PyRuntimeError::new_err(format!(
+            "Unable to use {threads} worker threads, the Tokio Runtime has already been started"
+        )));
+    }
+    WORKER_THREADS.store(threads, Ordering::SeqCst);
+    Ok(())
 }
 
 /// Utility to collect rust futures with GIL released
@@ -45,6 +135,120 @@ where
     py.allow_threads(|| runtime.block_on(f))
 }
 
+/// Utility to collect rust futures with GIL released that can be interrupted.
+///
+/// The future is dropped, which aborts the tasks spawned for the query, as soon
+/// as one of the following happens:
+/// * a python signal handler raises (e.g. `KeyboardInterrupt` on Ctrl-C)
+/// * the `token` is cancelled, raising `QueryCancelledError`
+/// * the `deadline` is reached, raising `QueryCancelledError`
+///
+/// Dropping a [`tokio::task::JoinHandle`] does not abort its task, so callers
+/// waiting on a spawned task must abort it themselves when an error is returned.
+pub(crate) fn wait_for_future_cancellable<F>(
+    py: Python,
+    f: F,
+    token: Option<&CancellationToken>,
+    deadline: Option<Instant>,
+) -> PyResult<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    let runtime: &Runtime = &get_tokio_runtime().0;
+    let token = token.cloned().unwrap_or_default();
+    py.allow_threads(|| {
+        runtime.block_on(async {
+            tokio::pin!(f);
+            let mut signal_check = tokio::time::interval(SIGNAL_CHECK_INTERVAL);
+            loop {
+                tokio::select! {
+                    biased;
+                    result = &mut f => return Ok(result),
+                    _ = token.cancelled() => {
+                        return Err(QueryCancelledError::new_err("Query was cancelled"));
+                    }
+                    _ = sleep_until(deadline) => {
+                        return Err(QueryCancelledError::new_err("Query exceeded its timeout"));
+                    }
+                    _ = signal_check.tick() => {
+                        Python::with_gil(|py| py.check_signals())?;
+                    }
+                }
+            }
+        })
+    })
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Convert a python `timeout` in seconds into the deadline of an execution
+pub(crate) fn timeout_to_deadline(timeout: Option<f64>) -> PyResult<Option<Instant>> {
+    timeout
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs)
+                .map(|timeout| Instant::now() + timeout)
+                .map_err(|_| PyValueError::new_err(format!("Invalid timeout: {secs}")))
+        })
+        .transpose()
+}
+
+/// Utility to convert a rust future into a python awaitable that is driven by
+/// the same Tokio Runtime as [`wait_for_future`]. Cancelling the awaitable
+/// drops the rust future.
+pub fn future_into_py<F, T>(py: Python, f: F) -> PyResult<Bound<PyAny>>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: IntoPy<PyObject>,
+{
+    pyo3_async_runtimes::generic::future_into_py::<AsyncRuntime, _, _>(py, f)
+}
+
+tokio::task_local! {
+    static TASK_LOCALS: OnceCell<TaskLocals>;
+}
+
+/// Spawns the futures of python awaitables on [`get_tokio_runtime`], so that they
+/// follow the runtime across a fork instead of using a runtime of their own
+struct AsyncRuntime;
+
+impl GenericRuntime for AsyncRuntime {
+    type JoinError = tokio::task::JoinError;
+    type JoinHandle = tokio::task::JoinHandle<()>;
+
+    fn spawn<F>(fut: F) -> Self::JoinHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        get_tokio_runtime().0.spawn(fut)
+    }
+}
+
+impl ContextExt for AsyncRuntime {
+    fn scope<F, R>(locals: TaskLocals, fut: F) -> Pin<Box<dyn Future<Output = R> + Send>>
+    where
+        F: Future<Output = R> + Send + 'static,
+    {
+        let cell = OnceCell::new();
+        let _ = cell.set(locals);
+        Box::pin(TASK_LOCALS.scope(cell, fut))
+    }
+
+    fn get_task_locals() -> Option<TaskLocals> {
+        TASK_LOCALS
+            .try_with(|c| {
+                c.get()
+                    .map(|locals| Python::with_gil(|py| locals.clone_ref(py)))
+            })
+            .unwrap_or_default()
+    }
+}
+
 pub(crate) fn parse_volatility(value: &str) -> Result<Volatility, DataFusionError> {
     Ok(match value {
         "immutable" => Volatility::Immutable,
@@ -58,3 +262,72 @@ pub(crate) fn parse_volatility(value: &str) -> Result<Volatility, DataFusionErro
         }
     })
 }
+
+/// Convert a single character option into a byte
+pub(crate) fn single_byte(name: &str, value: &str) -> PyResult<u8> {
+    match value.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(PyValueError::new_err(format!(
+            "{name} must be a single character"
+        ))),
+    }
+}
+
+/// Convert a python value into a [`ScalarValue`].
+///
+/// `pyarrow.Scalar`s of any type, including nested list, struct and map types,
+/// are converted through the C data interface without a round trip through
+/// python objects. Any other python value is first converted with
+/// `pyarrow.scalar` using `data_type`, if provided. The result is cast to
+/// `data_type` when the types differ.
+pub(crate) fn scalar_value_from_python(
+    value: &Bound<'_, PyAny>,
+    data_type: Option<&DataType>,
+) -> PyResult<ScalarValue> {
+    let py = value.py();
+    let pa = py.import_bound("pyarrow")?;
+
+    let value = if value.is_instance(&pa.getattr("Scalar")?)? {
+        value.clone()
+    } else {
+        let py_type = data_type.map(|t| t.to_pyarrow(py)).transpose()?;
+        pa.call_method1("scalar", (value, py_type))?
+    };
+
+    let array = pa.call_method1("repeat", (value, 1))?;
+    let array = make_array(ArrayData::from_pyarrow_bound(&array)?);
+    let scalar = ScalarValue::try_from_array(&array, 0)?;
+
+    match data_type {
+        Some(data_type) if scalar.data_type() != *data_type => Ok(scalar.cast_to(data_type)?),
+        _ => Ok(scalar),
+    }
+}
+
+/// Get the PyCapsule named `name` of one of the [`crate::ffi`] interfaces from
+/// an object that is either the capsule itself or provides it from a
+/// `__<name>__` method
+pub(crate) fn foreign_capsule<'py>(
+    obj: Bound<'py, PyAny>,
+    name: &str,
+) -> PyResult<Bound<'py, PyCapsule>> {
+    let method = format!("__{name}__");
+    let capsule = if obj.hasattr(method.as_str())? {
+        obj.call_method0(method.as_str())?
+    } else {
+        obj
+    };
+    let capsule = capsule.downcast_into::<PyCapsule>()?;
+    validate_pycapsule(&capsule, name)?;
+    Ok(capsule)
+}
+
+/// Export a value of one of the [`crate::ffi`] interfaces in a PyCapsule named `name`
+pub(crate) fn export_capsule<'py, T: 'static + Send>(
+    py: Python<'py>,
+    value: T,
+    name: &str,
+) -> PyResult<Bound<'py, PyCapsule>> {
+    let name = CString::new(name)?;
+    PyCapsule::new_bound(py, value, Some(name))
+}